@@ -400,6 +400,17 @@ fn print_instance_help(color: bool) {
     Print all songs that pass the query. Without value, lists all songs. The
     amount of printed information is affected by verbosity. See
     `{'c}uamp {'b}h {'w bold}query{'_}` for more info.
+
+  {'r}export{'w}=<file>{'gr}[:{'bold}<query>{'_bold}]{'_}
+    Export songs that pass the query to the playlist file. The format is
+    determined by the extension of the file ({'i}m3u{'_}, {'i}m3u8{'_} or
+    {'i}xspf{'_}). Without query, exports all songs. Tracks of cue sheets
+    are exported as {'i}file:///album.cue#track=2{'_}. See
+    `{'c}uamp {'b}h {'w bold}query{'_}` for more info.
+
+  {'r}lyrics  lyr{'_}
+    Print lyrics of the current song. They are read from {'i}.lrc{'_} file
+    next to the song or from the tags of the song.
 ",
     );
 }
@@ -654,6 +665,11 @@ fn print_control_messages_help(color: bool) {
     Shuffle the current playlist. The difference from {'r}sort{'w}=rng{'_} is
     that {'r}shuffle{'_} will respect the setting shuffle current.
 
+  {'r}shuffle-mode  psh{'gr}[=(true|false)]{'_}
+    Toggle/set the shuffle mode of the current playlist. Enabling it shuffles
+    the playlist (respecting the setting shuffle current) and disabling it
+    restores the original order. The current song stays current.
+
   {'r}sort-playlist  sort{'w}={'bold}<order>{'_bold}{'_}
     Sorts the songs in the current playlist. See
     `{'c}uamp {'b}h {'w bold}order{'_}` for more info.
@@ -672,6 +688,31 @@ fn print_control_messages_help(color: bool) {
     Seek back by the given duration. Without artument seeks by the default
     duration.
 
+  {'r}next-chapter  nch{'gr}[=<n>]{'_}
+    Jump to the start of the n-th next chapter of the current song.
+
+  {'r}previous-chapter  pch{'gr}[=<n>]{'_}
+    Jump to the start of the n-th previous chapter of the current song.
+
+  {'r}next-bookmark  nbm{'gr}[=<n>]{'_}
+    Jump to the n-th next bookmark of the current song.
+
+  {'r}previous-bookmark  pbm{'gr}[=<n>]{'_}
+    Jump to the n-th previous bookmark of the current song.
+
+  {'r}sleep{'w}=<duration>{'_}
+    Pause the playback after the given duration.
+
+  {'r}sleep-songs{'gr}[=<n>]{'_}
+    Pause the playback after n songs end. Without argument pauses after the
+    current song.
+
+  {'r}sleep-album{'_}
+    Pause the playback at the end of the current album.
+
+  {'r}sleep-cancel  no-sleep{'_}
+    Cancel the sleep timer.
+
   {'r}set-playlist  sp{'gr}[={'bold}<query>{'_bold}]{'_}
     Loads subset as the current playlist. Without value for {'w}filter{'_}
     loads all songs. See `{'c}uamp {'b}h {'w bold}query{'_}` for more info.
@@ -719,6 +760,54 @@ fn print_control_messages_help(color: bool) {
   {'r}untag  remove-tag{'w}=<name>:<query>{'_}
     Remove the given tag from all the songs that match the given query.
 
+  {'r}edit  edit-songs{'w}={'gr}[+|-]{'w}<changes>:<query>{'_}
+    Change metadata of all songs that match the query. Changes are comma
+    separated key value pairs such as {'i}n=title,p=artist1;artist2,y=1999{'_}.
+    Keys are the same as in filters. Empty value removes the field. `+` also
+    writes the changes to the audio files and `-` doesn't. By default this is
+    decided by the config option `write_tags`.
+
+  {'r}rate  rating{'w}=[<rating>][:<query>]{'_}
+    Set the rating (0 to 5) of all songs that match the query. Without the
+    query, rate the current song. Empty rating removes the rating.
+
+  {'r}clear-resume{'gr}[=<query>]{'_}
+    Remove the saved resume positions of songs that match the query, so that
+    they play from the start. Without the query, remove all positions.
+
+  {'r}bookmark  bm{'gr}[=<name>]{'_}
+    Add bookmark with the given name at the current position in the current
+    song. Without name, the current position is used as the name.
+
+  {'r}unbookmark  unbm{'w}=<name>{'_}
+    Remove bookmark with the given name from the current song.
+
+  {'r}pl-create{'w}=<name>{'_}
+    Create new empty named playlist. Named playlists are saved with the
+    library.
+
+  {'r}pl-append{'w}=<name>:<query>{'_}
+    Append songs that match the query to the end of the named playlist.
+
+  {'r}pl-remove{'w}=<name>:<query>{'_}
+    Remove songs that match the query from the named playlist.
+
+  {'r}pl-push{'w}=<name>{'_}
+    Push the named playlist to the playlist stack.
+
+  {'r}pl-delete{'w}=<name>{'_}
+    Delete the named playlist.
+
+  {'r}smart  push-smart{'w}=<name>{'_}
+    Push new playlist created from the smart playlist with the given name
+    (see config option `smart_playlists`). The playlist is reevaluated when
+    the library changes.
+
+  {'r}import{'w}=<file>{'_}
+    Push songs from the playlist file ({'i}m3u{'_}, {'i}m3u8{'_} or
+    {'i}xspf{'_}) to the playlist stack. Songs that are not in library are
+    added as temporary. Cue sheets without track add all their tracks.
+
   {'r}save{'_}
     Triggers save (but saves only if there is change).
 
@@ -739,6 +828,15 @@ fn print_control_messages_help(color: bool) {
                        playing song.
     Without value it is the same as setting it to `{'i}none{'_}`.
 
+  {'r}playlist-repeat  repeat-mode  prep{'gr}[=<repeat mode>]{'_}
+    Sets the repeat mode of the current playlist. It is one of the following:
+    - `{'i}-  none  off{'_}`       play the next song and end at the end of
+                          the playlist.
+    - `{'i}p  pl  playlist{'_}`    continue with the first song at the end of
+                          the playlist.
+    - `{'i}t  track  song{'_}`     repeat the current song.
+    Without value it is the same as setting it to `{'i}none{'_}`.
+
   {'r}restart{'gr}[=<exec-path>]{'_}
     Restart uamp. The optional argument gives path to new uamp binary that will
     take place of the current binary. If not present, uamp will try to get the
@@ -841,6 +939,9 @@ fn print_base_help(color: bool, header: bool) {
       {'r}tag{'w}=<name>{'_}
         All songs with the given tag in the order in which they were added the
         tag.
+
+      {'r}pl{'w}=<name>{'_}
+        All songs in the named playlist.
         
       {'w}<number>{'_}
         Playlist with the given index from the playlist stack. 0 is the current
@@ -876,6 +977,18 @@ fn print_filter_help(color: bool, header: bool) {
       {'r}a  alb  album{'w}:<pattern>{'_}
         Matches all songs where the album name matches.
 
+      {'r}c  comp  composer{'w}:<pattern>{'_}
+        Matches all songs where a composer matches.
+
+      {'r}cond  conductor{'w}:<pattern>{'_}
+        Matches all songs where a conductor matches.
+
+      {'r}pf  perf  performers{'w}:<pattern>{'_}
+        Matches all songs where a performer matches.
+
+      {'r}wr  writer  lyricist{'w}:<pattern>{'_}
+        Matches all songs where a lyricist matches.
+
       {'r}t  trk  track  track-number{'w}:<uint>{'_}
         Matches all songs with the given track number.
 
@@ -886,6 +999,13 @@ fn print_filter_help(color: bool, header: bool) {
       {'r}y  year{'w}:<int>{'_}
         Matches all songs with release within the given year.
 
+      {'r}len  length{'w}:<duration>{'_}
+        Matches all songs with the given length (in whole seconds).
+
+      {'r}r  rating{'w}:<uint>{'_}
+        Matches all songs with the given rating. Empty value matches songs
+        that are not rated.
+
       {'r}g  genre{'w}:<pattern>{'_}
         Matches all songs which genre that matches.
 
@@ -893,6 +1013,9 @@ fn print_filter_help(color: bool, header: bool) {
         Matches all songs with tag that matches. Consider using tag base where
         applicable as it is faster.
 
+      {'r}path{'w}:<pattern>{'_}
+        Matches all songs where the path to the song file matches.
+
     Instead of `{'i}:{'_}` you can use different separator to change the
     comparison:
       `{'i}={'_}` The string must match exactly.
@@ -901,6 +1024,12 @@ fn print_filter_help(color: bool, header: bool) {
           must match.
       `{'i}~{'_}` The string converted to lowercase asci without whitespace
           must contain the pattern (also converted in the same way).
+      `{'i}%{'_}` The string must match the regex pattern.
+      `{'i}?{'_}` Like `{'i}~{'_}`, but allows small typos in the pattern.
+
+    Numeric filters ({'r}t{'_}, {'r}d{'_}, {'r}y{'_}, {'r}len{'_} and {'r}r{'_}) may also use comparisons `{'i}<{'_}`, `{'i}<={'_}`,
+    `{'i}>{'_}` and `{'i}>={'_}`. Instead of single value, they also accept inclusive
+    range `{'w}<from>..<to>{'_}` where either of the bounds may be omitted.
     
     You can add `{'i}^{'_}` before the operator in place of `{'i}:{'_}` to
     negate the matching.
@@ -923,6 +1052,9 @@ fn print_filter_help(color: bool, header: bool) {
 
     Example filters:
       `{'i}alb:/smoke+mirrors/+alb:trench{'_}`
+      `{'i}y=1990..1999.len>8:00{'_}`
+      `{'i}path~/live/+n%/^intro.*/{'_}`
+      `{'i}s?imagin dragns{'_}`
 "
     );
 }
@@ -958,9 +1090,24 @@ fn print_order_help(color: bool, header: bool) {
       {'r}p  art  artist  performer  auth  author{'_}
         Sort by the artist.
 
+      {'r}c  comp  composer{'_}
+        Sort by the composer.
+
+      {'r}cond  conductor{'_}
+        Sort by the conductor.
+
+      {'r}pf  perf  performers{'_}
+        Sort by the performers.
+
+      {'r}wr  writer  lyricist{'_}
+        Sort by the lyricist.
+
       {'r}a  alb  album{'_}
         Sort by the album name.
 
+      {'r}aa  ap  album-artist{'_}
+        Sort by the album artist.
+
       {'r}t  trk  track  track-number{'_}
         Sort by the track number.
 
@@ -973,12 +1120,19 @@ fn print_order_help(color: bool, header: bool) {
       {'r}len  length{'_}
         Sort by the length of the song.
 
+      {'r}r  rating{'_}
+        Sort by the rating.
+
       {'r}g  genre{'_}
         Sort by the genre.
 
       {'r}tag{'_}
         Sort by tags.
 
+      {'r}rel  relevance{'_}
+        Sort by how well the songs match the filter of the query. The best
+        matches are first.
+
     You can alter the sorting with one of the following options (some
     parameters ignore some of this):
       `{'i}<  /{'_}`    sort in ascending order (this is the default).
@@ -988,6 +1142,10 @@ fn print_order_help(color: bool, header: bool) {
 
     If the complexity of the sorting is not set, it will use the default from
     settings.
+
+    Strings are sorted by their sort names if they are tagged. Unless disabled
+    in settings, they are compared without accents and numbers in them are
+    compared by value.
 "
     );
 }
@@ -1011,6 +1169,18 @@ fn print_unique_help(color: bool, header: bool) {
       {'r}p  art  artist  performer  auth  author{'_}
         Artists will be unique.
 
+      {'r}c  comp  composer{'_}
+        Composers will be unique.
+
+      {'r}cond  conductor{'_}
+        Conductors will be unique.
+
+      {'r}pf  perf  performers{'_}
+        Performers will be unique.
+
+      {'r}wr  writer  lyricist{'_}
+        Lyricists will be unique.
+
       {'r}a  alb  album{'_}
         Album names will be unique.
 
@@ -5,11 +5,13 @@ use pareg::{Pareg, has_any_key, parse_arg};
 use crate::core::{
     Result,
     config::Config,
+    library::write_playlist_file,
     server::{ReqMsg, SndMsg, client::Client},
 };
 
 use super::{
-    Props, help::help_instance, playlist_range::PlaylistRange, port::Port,
+    PlaylistExport, Props, help::help_instance, playlist_range::PlaylistRange,
+    port::Port,
 };
 
 //===========================================================================//
@@ -73,6 +75,16 @@ impl Instance {
                         Intention::Default,
                     ));
                 }
+                "lyrics" | "lyr" => self
+                    .messages
+                    .push((ReqMsg::Lyrics.into(), Intention::Default)),
+                v if has_any_key!(v, '=', "export") => {
+                    let e: PlaylistExport = args.cur_val('=')?;
+                    self.messages.push((
+                        SndMsg::Export(e.path, e.query),
+                        Intention::Default,
+                    ));
+                }
                 "-h" | "-?" | "--help" => help_instance(color),
                 "-p" | "--port" => {
                     self.port = Some(args.next_arg::<Port>()?.0)
@@ -133,6 +145,14 @@ impl Instance {
                         send_time,
                     );
                 }
+                SndMsg::Req(ReqMsg::Lyrics) => {
+                    let lyrics = client.req_lyrics().await?;
+                    props.print_style.lyrics(lyrics.as_deref(), props.color);
+                }
+                SndMsg::Export(p, q) => {
+                    let songs = client.req_query(&q).await?;
+                    write_playlist_file(p, &songs)?;
+                }
             }
         }
 
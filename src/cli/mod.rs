@@ -5,6 +5,7 @@ mod help;
 mod instance;
 mod internal;
 mod man;
+mod playlist_export;
 mod playlist_range;
 mod port;
 mod printers;
@@ -18,6 +19,6 @@ mod update;
 //===========================================================================//
 
 pub use self::{
-    action::*, args::*, config::*, instance::*, man::*, playlist_range::*,
-    props::*, run::*, shell::*, update::*,
+    action::*, args::*, config::*, instance::*, man::*, playlist_export::*,
+    playlist_range::*, props::*, run::*, shell::*, update::*,
 };
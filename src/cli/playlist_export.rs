@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use pareg::{ArgError, ArgInto, FromArg};
+
+use crate::core::{library::PlaylistFormat, query::Query};
+
+/// Path to playlist file and the query of songs to export.
+#[derive(Debug)]
+pub struct PlaylistExport {
+    pub path: PathBuf,
+    pub query: Query,
+}
+
+impl<'a> FromArg<'a> for PlaylistExport {
+    fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+        let (path, query) = match arg.split_once(':') {
+            Some((p, q)) => (
+                p,
+                q.arg_into::<Query>()
+                    .map_err(|e| e.shift_span(p.len() + 1, arg.to_string()))?,
+            ),
+            None => (arg, Query::default()),
+        };
+
+        if PlaylistFormat::from_path(path).is_err() {
+            return ArgError::invalid_value("Unknown playlist format.", arg)
+                .hint("Supported extensions are `m3u`, `m3u8` and `xspf`.")
+                .err();
+        }
+
+        Ok(Self {
+            path: path.into(),
+            query,
+        })
+    }
+}
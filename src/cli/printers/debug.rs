@@ -2,7 +2,10 @@ use std::time::Instant;
 
 use crate::{
     cli::printers::ser,
-    core::{library::Song, server::Info},
+    core::{
+        library::{Lyrics, Song},
+        server::Info,
+    },
 };
 
 pub fn info(info: &Info) {
@@ -12,3 +15,7 @@ pub fn info(info: &Info) {
 pub fn song_list(songs: &[Song], send_time: Instant) {
     println!("{:#?}", ser::SongList::new(songs, send_time));
 }
+
+pub fn lyrics(lyrics: Option<&Lyrics>) {
+    println!("{lyrics:#?}");
+}
@@ -1,6 +1,9 @@
 use std::{io, time::Instant};
 
-use crate::core::{library::Song, server::Info};
+use crate::core::{
+    library::{Lyrics, Song},
+    server::Info,
+};
 
 use super::ser;
 
@@ -19,3 +22,8 @@ pub fn song_list(songs: &[Song], send_time: Instant) {
     );
     println!();
 }
+
+pub fn lyrics(lyrics: Option<&Lyrics>) {
+    _ = serde_json::to_writer_pretty(io::stdout().lock(), &lyrics);
+    println!();
+}
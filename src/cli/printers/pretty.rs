@@ -14,7 +14,8 @@ use crate::{
     cli::Props,
     core::{
         config::{CacheSize, Config},
-        library::{Song, img_lookup::lookup_image_data_song},
+        library::{Lyrics, Song, img_lookup::lookup_image_data_song},
+        player::SleepMode,
         server::Info,
     },
     ext::duration_to_string,
@@ -143,11 +144,14 @@ pub fn playlist_config(info: &Info, color: bool) {
     */
 
     let playlist_stack = info.playlist_stack();
-    let config = format!(
+    let mut config = format!(
         "end: {} | add: {}",
         info.playlist_end(),
         info.playlist_add_policy
     );
+    if let Some(sleep) = info.sleep() {
+        config += &format!(" | sleep: {sleep}");
+    }
 
     printmcln!(
         color,
@@ -231,6 +235,20 @@ pub fn verbose_song_list(songs: &[Song], color: bool, send_time: Instant) {
     );
 }
 
+pub fn lyrics(lyrics: Option<&Lyrics>, color: bool) {
+    let Some(lyrics) = lyrics else {
+        printmcln!(color, "{'gr}No lyrics.{'_}");
+        return;
+    };
+
+    for l in &lyrics.lines {
+        if let Some(t) = l.time {
+            printmc!(color, "{'gr}[{:>5}]{'_} ", duration_to_string(t, true));
+        }
+        println!("{}", l.text);
+    }
+}
+
 fn print_song(s: &Song, color: bool) {
     printmc!(color, "{'y}");
     print_elipsised(s.title_str(), 30);
@@ -421,6 +439,15 @@ impl Info {
             .map_or("--".into(), |a| a.to_string().into())
     }
 
+    fn sleep(&self) -> Option<Cow<'static, str>> {
+        self.sleep_timer.map(|s| match s {
+            SleepMode::Time(d) => duration_to_string(d, true).into(),
+            SleepMode::Songs(1) => "1 song".into(),
+            SleepMode::Songs(n) => format!("{n} songs").into(),
+            SleepMode::Album => "album".into(),
+        })
+    }
+
     fn now_playing_str(&self, f: impl Fn(&Song) -> &str) -> &str {
         self.now_playing.as_ref().map_or("--", f)
     }
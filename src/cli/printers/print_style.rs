@@ -4,7 +4,11 @@ use pareg::FromArg;
 
 use crate::{
     cli::Props,
-    core::{config::Config, library::Song, server::Info},
+    core::{
+        config::Config,
+        library::{Lyrics, Song},
+        server::Info,
+    },
 };
 
 use super::{debug, json, pretty};
@@ -38,4 +42,12 @@ impl PrintStyle {
             PrintStyle::Json => json::song_list(songs, send_time),
         }
     }
+
+    pub fn lyrics(&self, lyrics: Option<&Lyrics>, color: bool) {
+        match self {
+            PrintStyle::Pretty => pretty::lyrics(lyrics, color),
+            PrintStyle::Debug => debug::lyrics(lyrics),
+            PrintStyle::Json => json::lyrics(lyrics),
+        }
+    }
 }
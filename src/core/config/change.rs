@@ -16,6 +16,11 @@ bitflags! {
         const CACHE_PATH = 0x800;
         const FADE_PLAY_PAUSE = 0x1000;
         const GAPLESS = 0x2000;
+        const PLAY_THRESHOLD = 0x4000;
+        const REPLAY_GAIN = 0x8000;
+        const CROSSFADE = 0x10000;
+        const WATCH_LIBRARY = 0x20000;
+        const RESUME = 0x40000;
     }
 }
 
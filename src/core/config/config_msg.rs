@@ -42,6 +42,12 @@ impl Config {
         if self.audio_extensions() != new.audio_extensions() {
             res |= Change::AUDIO_EXTENSIONS;
         }
+        if self.recursive_search() != new.recursive_search() {
+            res |= Change::RECURSIVE_SEARCH;
+        }
+        if self.watch_library() != new.watch_library() {
+            res |= Change::WATCH_LIBRARY;
+        }
         if self.server_address() != new.server_address() {
             res |= Change::SERVER_ADDRESS;
         }
@@ -69,6 +75,24 @@ impl Config {
         if self.gapless() != new.gapless() {
             res |= Change::GAPLESS;
         }
+        if self.crossfade() != new.crossfade()
+            || self.crossfade_albums() != new.crossfade_albums()
+        {
+            res |= Change::CROSSFADE;
+        }
+        if self.play_threshold() != new.play_threshold() {
+            res |= Change::PLAY_THRESHOLD;
+        }
+        if self.resume_length() != new.resume_length()
+            || self.resume_tags() != new.resume_tags()
+        {
+            res |= Change::RESUME;
+        }
+        if self.replay_gain() != new.replay_gain()
+            || self.replay_gain_preamp() != new.replay_gain_preamp()
+        {
+            res |= Change::REPLAY_GAIN;
+        }
 
         new.change();
         *self = new;
@@ -169,6 +193,14 @@ impl UampApp {
             self.start_get_new_songs(ctrl, LoadOpts::default())?;
         }
 
+        if change.intersects(
+            Change::SEARCH_PATHS
+                | Change::RECURSIVE_SEARCH
+                | Change::WATCH_LIBRARY,
+        ) {
+            self.rewatch_library()?;
+        }
+
         let restart_server = change.intersects(
             Change::SERVER_ADDRESS | Change::PORT | Change::ENABLE_SERVER,
         );
@@ -210,6 +242,31 @@ impl UampApp {
             self.player.gapless(self.config.gapless());
         }
 
+        if change.contains(Change::CROSSFADE) {
+            self.player.crossfade(
+                self.config.crossfade().0,
+                self.config.crossfade_albums(),
+            );
+        }
+
+        if change.contains(Change::PLAY_THRESHOLD) {
+            self.player.set_play_threshold(self.config.play_threshold());
+        }
+
+        if change.contains(Change::RESUME) {
+            self.player.set_resume(
+                self.config.resume_length().map(|l| l.0),
+                self.config.resume_tags().clone(),
+            );
+        }
+
+        if change.contains(Change::REPLAY_GAIN) {
+            self.player.replay_gain(
+                self.config.replay_gain(),
+                self.config.replay_gain_preamp(),
+            );
+        }
+
         if self.config.changed() {
             self.client_update(SubMsg::ConfigChanged(
                 sub::Config::new(&self.config).into(),
@@ -1,18 +1,20 @@
 use serde::{Deserialize, Serialize};
-use std::{cell::Cell, collections::HashMap, path::PathBuf, time::Duration};
+use std::{
+    cell::Cell, collections::HashMap, path::PathBuf, sync::Arc, time::Duration,
+};
 use uamp_proc::{JsonValueUpdate, PartialClone, TrackChange};
 
 use crate::{
     core::{
         Alias, ControlFunction, Error, Result,
-        config::{AutoTag, Change, Version, default},
-        query::Base,
+        config::{AutoTag, Change, SmartPlaylist, Version, default},
+        query::{Base, SortOpts},
     },
     env::{RunType, install},
     ext::Wrap,
 };
 
-use super::{CacheSize, song_pos_save::SongPosSave};
+use super::{CacheSize, ReplayGainMode, song_pos_save::SongPosSave};
 
 #[derive(
     Debug,
@@ -120,12 +122,31 @@ pub struct Config {
     #[serde(default = "default::default_base")]
     default_base: Base,
 
+    /// Songs with any of these tags remember the position where they were
+    /// left.
+    #[track_ref(pub, pub)]
+    #[serde(default)]
+    #[value_change(Change::RESUME)]
+    resume_tags: Vec<Arc<str>>,
+
+    /// Named queries that can be loaded as playlists. The playlists are
+    /// reevaluated when the library changes.
+    #[track_ref(pub, pub)]
+    #[serde(default)]
+    smart_playlists: HashMap<String, SmartPlaylist>,
+
     // fields passed by value:
     /// When enabled uamp will sort only by the primary attribute.
     #[track_value(pub, pub, eq)]
     #[serde(default)]
     simple_sorting: bool,
 
+    /// When enabled, strings are sorted with natural accent insensitive
+    /// collation (e.g. `Track 2` before `Track 10`).
+    #[track_value(pub, pub, eq)]
+    #[serde(default = "default::natural_sorting")]
+    natural_sorting: bool,
+
     /// When enabled, uamp will start playing immidietly when it
     /// starts.
     #[track_value(pub, pub, eq)]
@@ -151,6 +172,25 @@ pub struct Config {
     #[serde(default = "default::update_library_on_start")]
     update_library_on_start: bool,
 
+    /// When enabled, uamp will watch the search paths for changes and
+    /// apply them to the library.
+    #[track_value(pub, pub, eq)]
+    #[serde(default)]
+    #[value_change(Change::WATCH_LIBRARY)]
+    watch_library: bool,
+
+    /// Number of threads used when loading new songs. 0 means the number of
+    /// available cpu cores.
+    #[track_value(pub, pub, eq)]
+    #[serde(default)]
+    scan_threads: usize,
+
+    /// When enabled, changes to song metadata are also written to the tags
+    /// of the audio files by default.
+    #[track_value(pub, pub, eq)]
+    #[serde(default)]
+    write_tags: bool,
+
     /// When enbled, non existing songs will be removed from library
     /// when looking for new songs.
     #[track_value(pub, pub, eq)]
@@ -179,12 +219,57 @@ pub struct Config {
     #[value_change(Change::FADE_PLAY_PAUSE)]
     fade_play_pause: Wrap<Duration>,
 
+    /// Length of the volume fade when the sleep timer pauses the playback.
+    /// [`None`] uses `fade_play_pause`.
+    #[track_value(pub, pub, eq)]
+    #[serde(default = "default::sleep_fade")]
+    sleep_fade: Option<Wrap<Duration>>,
+
     /// Enable/Disable gapless playback.
     #[track_value(pub, pub, eq)]
     #[serde(default = "default::gapless")]
     #[value_change(Change::GAPLESS)]
     gapless: bool,
 
+    /// Length of crossfade between consecutive songs. Zero disables
+    /// crossfade.
+    #[track_value(pub, pub, eq)]
+    #[serde(default = "default::crossfade")]
+    #[value_change(Change::CROSSFADE)]
+    crossfade: Wrap<Duration>,
+
+    /// Crossfade also between consecutive songs from the same album.
+    #[track_value(pub, pub, eq)]
+    #[serde(default)]
+    #[value_change(Change::CROSSFADE)]
+    crossfade_albums: bool,
+
+    /// Portion of the song that must be played so that it is counted as
+    /// played.
+    #[track_value(pub, pub, eq)]
+    #[serde(default = "default::play_threshold")]
+    #[value_change(Change::PLAY_THRESHOLD)]
+    play_threshold: f32,
+
+    /// Songs at least this long remember the position where they were left.
+    /// [`None`] disables this.
+    #[track_value(pub, pub, eq)]
+    #[serde(default = "default::resume_length")]
+    #[value_change(Change::RESUME)]
+    resume_length: Option<Wrap<Duration>>,
+
+    /// Determines how is the loudness of songs normalized.
+    #[track_value(pub, pub, eq)]
+    #[serde(default)]
+    #[value_change(Change::REPLAY_GAIN)]
+    replay_gain: ReplayGainMode,
+
+    /// Gain in dB that is added to the replay gain.
+    #[track_value(pub, pub, eq)]
+    #[serde(default)]
+    #[value_change(Change::REPLAY_GAIN)]
+    replay_gain_preamp: f32,
+
     /// Detemines how much uamp seeks with fast forward/rewind
     #[track_value(pub, pub, eq)]
     #[serde(default = "default::seek_jump")]
@@ -290,6 +375,14 @@ impl Config {
         self.cache_path().join(format!("cover{size}"))
     }
 
+    /// Gets the default options for sorting songs.
+    pub fn sort_opts(&self) -> SortOpts {
+        SortOpts {
+            simple: self.simple_sorting(),
+            natural: self.natural_sorting(),
+        }
+    }
+
     pub fn should_start_server(&self) -> bool {
         self.force_server.unwrap_or(self.enable_server())
     }
@@ -133,6 +133,10 @@ pub fn default_base() -> Base {
     Base::Tag("base".into())
 }
 
+pub fn natural_sorting() -> bool {
+    true
+}
+
 pub fn shuffle_current() -> bool {
     true
 }
@@ -165,10 +169,26 @@ pub fn fade_play_pause() -> Wrap<Duration> {
     Wrap(Duration::from_millis(150))
 }
 
+pub fn sleep_fade() -> Option<Wrap<Duration>> {
+    Some(Wrap(Duration::from_secs(10)))
+}
+
+pub fn crossfade() -> Wrap<Duration> {
+    Wrap(Duration::ZERO)
+}
+
 pub fn gapless() -> bool {
     true
 }
 
+pub fn play_threshold() -> f32 {
+    0.5
+}
+
+pub fn resume_length() -> Option<Wrap<Duration>> {
+    Some(Wrap(Duration::from_secs(30 * 60)))
+}
+
 pub fn seek_jump() -> Wrap<Duration> {
     Wrap(Duration::from_secs(10))
 }
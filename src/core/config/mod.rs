@@ -6,6 +6,8 @@ mod config_struct;
 pub mod default;
 mod json;
 mod migrate;
+mod replay_gain_mode;
+mod smart_playlist;
 mod song_pos_save;
 mod version;
 
@@ -17,7 +19,7 @@ use std::path::PathBuf;
 
 pub use self::{
     auto_tag::*, cache_size::*, change::*, config_msg::*, config_struct::*,
-    song_pos_save::*, version::*,
+    replay_gain_mode::*, smart_playlist::*, song_pos_save::*, version::*,
 };
 
 /// Unique app identifier, it is different when debugging.
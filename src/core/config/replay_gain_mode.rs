@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Determines which replay gain is used to normalize the loudness of songs.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default,
+)]
+pub enum ReplayGainMode {
+    /// Don't normalize the loudness.
+    #[default]
+    Off,
+    /// Normalize the loudness of each track.
+    Track,
+    /// Normalize the loudness of albums. Loudness differences within album
+    /// are preserved.
+    Album,
+}
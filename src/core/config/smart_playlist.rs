@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::query::Query;
+
+/// Playlist defined by query. It is serialized as the string form of the
+/// query.
+#[derive(Debug, Clone)]
+pub struct SmartPlaylist(pub Query);
+
+impl Serialize for SmartPlaylist {
+    fn serialize<S>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SmartPlaylist {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map(Self)
+            .map_err(|e| {
+                serde::de::Error::custom(format!("Invalid value: {e}"))
+            })
+    }
+}
@@ -14,8 +14,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::{
         AppCtrl, ErrKind, Error, Msg, Result, UampApp,
-        library::LoadOpts,
-        player::AddPolicy,
+        library::{LoadOpts, Song},
+        player::{AddPolicy, RepeatMode, SleepMode},
         query::SongOrder,
         server::{
             SubMsg,
@@ -50,6 +50,9 @@ pub enum ControlMsg {
     Mute(Option<bool>),
     /// Shuffle the current playlist
     Shuffle,
+    /// Toggle/set the shuffle mode of the current playlist, [`None`] to
+    /// toggle, [`Some`] to set
+    SetShuffle(Option<bool>),
     /// Jump to the given index in the playlist
     PlaylistJump(usize),
     /// Exit the app
@@ -71,12 +74,26 @@ pub enum ControlMsg {
     Flatten(usize),
     /// Set the playlist add policy.
     SetPlaylistAddPolicy(AddPolicy),
+    /// Set the repeat mode of the current playlist.
+    SetPlaylistRepeat(RepeatMode),
     /// Thriggers save
     Save,
     /// Ends the current playlist triggering the playlist end action.
     EndPlaylist,
     /// Remove playlist at the given index. Top playlist is 0.
     RemovePlaylist(usize),
+    /// Seek to the start of the Nth next chapter of the current song.
+    NextChapter(usize),
+    /// Seek to the start of the Nth previous chapter of the current song.
+    PrevChapter(usize),
+    /// Seek to the Nth next bookmark of the current song.
+    NextBookmark(usize),
+    /// Seek to the Nth previous bookmark of the current song.
+    PrevBookmark(usize),
+    /// Set the sleep timer that pauses the playback.
+    SetSleep(SleepMode),
+    /// Cancel the sleep timer.
+    CancelSleep,
 }
 
 impl UampApp {
@@ -145,6 +162,13 @@ impl UampApp {
                     SubMsg::SetPlaylist(p.into())
                 });
             }
+            ControlMsg::SetShuffle(b) => {
+                let b = b.unwrap_or(!self.player.playlist().is_shuffled());
+                self.player.set_shuffle(b, self.config.shuffle_current());
+                self.client_update_set_playlist(|p| {
+                    SubMsg::SetPlaylist(p.into())
+                });
+            }
             ControlMsg::SetVolume(v) => {
                 self.player.change_volume(v.clamp(0., 1.));
                 self.client_update(SubMsg::SetVolume(self.player.volume()));
@@ -212,7 +236,7 @@ impl UampApp {
             ControlMsg::SortPlaylist(ord) => {
                 self.player.mut_playlist().sort(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     ord,
                 );
                 self.client_update_set_playlist(|p| {
@@ -236,6 +260,10 @@ impl UampApp {
                 self.player.mut_playlist().add_policy = policy;
                 self.client_update(SubMsg::SetPlaylistAddPolicy(policy));
             }
+            ControlMsg::SetPlaylistRepeat(mode) => {
+                self.player.set_repeat(mode);
+                self.client_update(SubMsg::SetPlaylistRepeat(mode));
+            }
             ControlMsg::Save => self.save_all(false, ctrl)?,
             ControlMsg::EndPlaylist => {
                 self.player.end_playlist(&mut self.library);
@@ -278,6 +306,28 @@ impl UampApp {
                     self.client_update(SubMsg::RemovePlaylist(idx));
                 }
             }
+            ControlMsg::NextChapter(n) => {
+                return self.mark_jump("chapter", n, true, |s| {
+                    s.chapters().iter().map(|c| c.start).collect()
+                });
+            }
+            ControlMsg::PrevChapter(n) => {
+                return self.mark_jump("chapter", n, false, |s| {
+                    s.chapters().iter().map(|c| c.start).collect()
+                });
+            }
+            ControlMsg::NextBookmark(n) => {
+                return self.mark_jump("bookmark", n, true, |s| {
+                    s.bookmarks().iter().map(|b| b.time).collect()
+                });
+            }
+            ControlMsg::PrevBookmark(n) => {
+                return self.mark_jump("bookmark", n, false, |s| {
+                    s.bookmarks().iter().map(|b| b.time).collect()
+                });
+            }
+            ControlMsg::SetSleep(mode) => self.set_sleep(ctrl, mode)?,
+            ControlMsg::CancelSleep => self.cancel_sleep(),
         };
 
         Ok(vec![])
@@ -307,6 +357,8 @@ impl Display for ControlMsg {
             ControlMsg::Mute(None) => f.write_str("mute"),
             ControlMsg::Mute(Some(v)) => write!(f, "mute={v}"),
             ControlMsg::Shuffle => f.write_str("shuffle"),
+            ControlMsg::SetShuffle(None) => f.write_str("psh"),
+            ControlMsg::SetShuffle(Some(v)) => write!(f, "psh={v}"),
             ControlMsg::PlaylistJump(v) => write!(f, "pj={v}"),
             ControlMsg::Close => f.write_char('x'),
             ControlMsg::LoadNewSongs(o) => {
@@ -337,9 +389,27 @@ impl Display for ControlMsg {
                 f.write_str("pap")
             }
             ControlMsg::SetPlaylistAddPolicy(p) => write!(f, "pap={p}"),
+            ControlMsg::SetPlaylistRepeat(RepeatMode::None) => {
+                f.write_str("prep")
+            }
+            ControlMsg::SetPlaylistRepeat(m) => write!(f, "prep={m}"),
             ControlMsg::Save => f.write_str("save"),
             ControlMsg::EndPlaylist => f.write_str("end-playlist"),
             ControlMsg::RemovePlaylist(p) => write!(f, "remove-playlist={p}"),
+            ControlMsg::NextChapter(n) => write!(f, "nch={n}"),
+            ControlMsg::PrevChapter(n) => write!(f, "pch={n}"),
+            ControlMsg::NextBookmark(n) => write!(f, "nbm={n}"),
+            ControlMsg::PrevBookmark(n) => write!(f, "pbm={n}"),
+            ControlMsg::SetSleep(SleepMode::Time(d)) => {
+                write!(f, "sleep={}", duration_to_string(*d, false))
+            }
+            ControlMsg::SetSleep(SleepMode::Songs(n)) => {
+                write!(f, "sleep-songs={n}")
+            }
+            ControlMsg::SetSleep(SleepMode::Album) => {
+                f.write_str("sleep-album")
+            }
+            ControlMsg::CancelSleep => f.write_str("sleep-cancel"),
         }
     }
 }
@@ -391,6 +461,9 @@ impl FromStr for ControlMsg {
                 ))
             }
             "shuffle-playlist" | "shuffle" => Ok(ControlMsg::Shuffle),
+            v if has_any_key!(v, '=', "shuffle-mode", "psh") => {
+                Ok(ControlMsg::SetShuffle(mval_arg(v, '=')?))
+            }
             "exit" | "close" | "x" => Ok(ControlMsg::Close),
             v if has_any_key!(v, '=', "seek-to", "seek") => {
                 Ok(ControlMsg::SeekTo(val_arg::<Wrap<Duration>>(v, '=')?.0))
@@ -426,6 +499,18 @@ impl FromStr for ControlMsg {
                     mval_arg(v, '=')?.unwrap_or_default(),
                 ))
             }
+            v if has_any_key!(
+                v,
+                '=',
+                "playlist-repeat",
+                "repeat-mode",
+                "prep"
+            ) =>
+            {
+                Ok(ControlMsg::SetPlaylistRepeat(
+                    mval_arg(v, '=')?.unwrap_or_default(),
+                ))
+            }
             "save" => Ok(ControlMsg::Save),
             "end-playlist" => Ok(ControlMsg::EndPlaylist),
             v if has_any_key!(v, '=', "remove-playlist") => {
@@ -433,6 +518,28 @@ impl FromStr for ControlMsg {
                     mval_arg(v, '=')?.unwrap_or_default(),
                 ))
             }
+            v if has_any_key!(v, '=', "next-chapter", "nch") => {
+                Ok(ControlMsg::NextChapter(mval_arg(v, '=')?.unwrap_or(1)))
+            }
+            v if has_any_key!(v, '=', "previous-chapter", "pch") => {
+                Ok(ControlMsg::PrevChapter(mval_arg(v, '=')?.unwrap_or(1)))
+            }
+            v if has_any_key!(v, '=', "next-bookmark", "nbm") => {
+                Ok(ControlMsg::NextBookmark(mval_arg(v, '=')?.unwrap_or(1)))
+            }
+            v if has_any_key!(v, '=', "previous-bookmark", "pbm") => {
+                Ok(ControlMsg::PrevBookmark(mval_arg(v, '=')?.unwrap_or(1)))
+            }
+            v if has_any_key!(v, '=', "sleep") => Ok(ControlMsg::SetSleep(
+                SleepMode::Time(val_arg::<Wrap<Duration>>(v, '=')?.0),
+            )),
+            v if has_any_key!(v, '=', "sleep-songs") => {
+                Ok(ControlMsg::SetSleep(SleepMode::Songs(
+                    mval_arg(v, '=')?.unwrap_or(1),
+                )))
+            }
+            "sleep-album" => Ok(ControlMsg::SetSleep(SleepMode::Album)),
+            "sleep-cancel" | "no-sleep" => Ok(ControlMsg::CancelSleep),
             v => ArgError::from_msg(
                 ArgErrKind::UnknownArgument,
                 "Unknown control message.",
@@ -449,6 +556,61 @@ impl FromArgStr for ControlMsg {}
 //                                  Private                                  //
 //===========================================================================//
 
+/// Marks (chapters or bookmarks) closer than this to the current position
+/// are skipped when jumping. This way jumping back from the middle of chapter
+/// restarts it and inexact seeks don't jump to the same mark again.
+const MARK_TOLERANCE: Duration = Duration::from_secs(1);
+
+impl UampApp {
+    /// Seeks to the `n`th next or previous mark in the current song.
+    /// `marks` gets the ordered times of the marks of the song and `what` is
+    /// the name of the marks used in errors.
+    fn mark_jump(
+        &mut self,
+        what: &str,
+        n: usize,
+        forward: bool,
+        marks: impl Fn(&Song) -> Vec<Duration>,
+    ) -> Result<Vec<Msg>> {
+        let Some(id) = self.player.now_playing() else {
+            return Error::invalid_operation()
+                .msg(format!("Cannot jump to {what}."))
+                .reason("There is no current song.")
+                .err();
+        };
+        let cur = self
+            .player
+            .timestamp()
+            .map_or(Duration::ZERO, |t| t.current);
+        let marks = marks(&self.library[id]);
+
+        let n = n.saturating_sub(1);
+        let target = if forward {
+            marks
+                .into_iter()
+                .filter(|m| *m > cur + MARK_TOLERANCE)
+                .nth(n)
+        } else {
+            marks
+                .into_iter()
+                .rev()
+                .filter(|m| *m + MARK_TOLERANCE < cur)
+                .nth(n)
+        };
+
+        let Some(t) = target else {
+            return Error::invalid_operation()
+                .msg(format!("Cannot jump to {what}."))
+                .reason(format!(
+                    "There is no {what} {} the current position.",
+                    if forward { "after" } else { "before" }
+                ))
+                .err();
+        };
+        Ok(vec![Msg::Control(ControlMsg::SeekTo(t))])
+    }
+}
+
 #[derive(FromArg)]
 enum PlayPause {
     Play,
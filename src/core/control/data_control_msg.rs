@@ -3,6 +3,7 @@ use std::{
     fmt::Display,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
 };
 
 use itertools::Itertools;
@@ -12,17 +13,24 @@ use pareg::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::core::{
-    Alias, AppCtrl, Msg, Result, UampApp,
-    control::types::{AddTag, RemoveTag},
-    query::{ComposedFilter, Query},
-    server::{
-        SubMsg,
-        sub::{
-            self, InsertIntoPlaylist, PlaylistJump, RemoveFromPlaylist,
-            ReorderPlaylistStack,
+use crate::{
+    core::{
+        Alias, AppCtrl, Error, Msg, Result, UampApp,
+        control::types::{
+            AddTag, EditSongs, PlaylistSongs, RateSongs, RemoveTag,
+        },
+        library::{Bookmark, read_playlist_file},
+        player::Playlist,
+        query::{ComposedFilter, Query},
+        server::{
+            SubMsg,
+            sub::{
+                self, InsertIntoPlaylist, NamedPlaylist, PlaylistJump,
+                RemoveFromPlaylist, ReorderPlaylistStack,
+            },
         },
     },
+    ext::duration_to_string,
 };
 
 //===========================================================================//
@@ -62,13 +70,39 @@ pub enum DataControlMsg {
     AddTag(AddTag),
     /// Remove the given tag from all songs specified by the query.
     RemoveTag(RemoveTag),
+    /// Change the metadata of songs specified by the query.
+    EditSongs(EditSongs),
+    /// Set the rating of the current song or songs specified by the query.
+    RateSongs(RateSongs),
+    /// Remove the resume positions of songs specified by the query.
+    ClearResume(Query),
+    /// Add bookmark with the given name at the current position in the
+    /// current song. [`None`] names the bookmark by its time.
+    AddBookmark(Option<String>),
+    /// Remove bookmarks with the given name from the current song.
+    RemoveBookmark(String),
+    /// Create new empty named playlist.
+    CreateNamedPlaylist(Arc<str>),
+    /// Append songs specified by the query to the named playlist.
+    AppendToNamedPlaylist(PlaylistSongs),
+    /// Remove songs specified by the query from the named playlist.
+    RemoveFromNamedPlaylist(PlaylistSongs),
+    /// Push the named playlist to the playlist stack.
+    PushNamedPlaylist(Arc<str>),
+    /// Delete the named playlist.
+    DeleteNamedPlaylist(Arc<str>),
+    /// Push new playlist created from the smart playlist with the given name.
+    PushSmartPlaylist(String),
+    /// Push songs from the playlist file. Songs that are not in library are
+    /// added as temporary.
+    ImportPlaylist(PathBuf),
 }
 
 impl UampApp {
     /// Handles events for [`DataControlMsg`]
     pub(in crate::core) fn data_control_event(
         &mut self,
-        _ctrl: &mut AppCtrl,
+        ctrl: &mut AppCtrl,
         msg: DataControlMsg,
     ) -> Result<Vec<Msg>> {
         match msg {
@@ -80,7 +114,7 @@ impl UampApp {
             DataControlMsg::SetPlaylist(q) => {
                 let songs = q.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -96,7 +130,7 @@ impl UampApp {
             DataControlMsg::PushPlaylist(q) => {
                 let songs = q.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -112,7 +146,7 @@ impl UampApp {
             DataControlMsg::PushPlaylistAndCur(q) => {
                 let songs = q.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -124,7 +158,7 @@ impl UampApp {
             DataControlMsg::Queue(q) => {
                 let songs = q.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -138,7 +172,7 @@ impl UampApp {
             DataControlMsg::PlayNext(q) => {
                 let songs = q.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -178,7 +212,7 @@ impl UampApp {
             DataControlMsg::RemoveFromLibrary(q) => {
                 let songs = q.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -229,7 +263,7 @@ impl UampApp {
             DataControlMsg::AddTag(m) => {
                 let songs = m.query.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -244,7 +278,7 @@ impl UampApp {
             DataControlMsg::RemoveTag(m) => {
                 let songs = m.query.get_ids(
                     &self.library,
-                    self.config.simple_sorting(),
+                    self.config.sort_opts(),
                     self.config.default_base().clone(),
                     &self.player,
                 )?;
@@ -256,6 +290,179 @@ impl UampApp {
                     sub::RemoveTag::new(m.name, songs).into(),
                 ));
             }
+            DataControlMsg::EditSongs(m) => {
+                let songs = m.query.get_ids(
+                    &self.library,
+                    self.config.sort_opts(),
+                    self.config.default_base().clone(),
+                    &self.player,
+                )?;
+                if songs.is_empty() || m.edit.is_empty() {
+                    return Ok(vec![]);
+                }
+                self.library.edit_songs(&songs, &m.edit);
+                let res = if m.write.unwrap_or(self.config.write_tags()) {
+                    self.write_tags(ctrl, &songs, m.edit.clone())
+                } else {
+                    Ok(())
+                };
+                self.client_update(SubMsg::EditSongs(
+                    sub::EditSongs::new(songs, m.edit).into(),
+                ));
+                res?;
+            }
+            DataControlMsg::RateSongs(m) => {
+                let songs = if let Some(q) = m.query {
+                    q.get_ids(
+                        &self.library,
+                        self.config.sort_opts(),
+                        self.config.default_base().clone(),
+                        &self.player,
+                    )?
+                } else if let Some(s) = self.player.playlist().current() {
+                    vec![s]
+                } else {
+                    return Error::invalid_operation()
+                        .msg("There is no current song to rate.")
+                        .err();
+                };
+                if songs.is_empty() {
+                    return Ok(vec![]);
+                }
+                self.library.rate_songs(&songs, m.rating);
+                self.client_update(SubMsg::SetRating(
+                    sub::SetRating::new(songs, m.rating).into(),
+                ));
+            }
+            DataControlMsg::ClearResume(q) => {
+                let songs = q.get_ids(
+                    &self.library,
+                    self.config.sort_opts(),
+                    self.config.default_base().clone(),
+                    &self.player,
+                )?;
+                for id in self.library.clear_resume(songs) {
+                    self.client_update(SubMsg::SongStats(
+                        sub::SongStats::new(id, &self.library[id]),
+                    ));
+                }
+            }
+            DataControlMsg::AddBookmark(name) => {
+                let (Some(id), Some(ts)) =
+                    (self.player.now_playing(), self.player.timestamp())
+                else {
+                    return Error::invalid_operation()
+                        .msg("Cannot add bookmark.")
+                        .reason("Nothing is playing.")
+                        .err();
+                };
+                let name = name
+                    .unwrap_or_else(|| duration_to_string(ts.current, true));
+                self.library.add_bookmark(
+                    id,
+                    Bookmark {
+                        name,
+                        time: ts.current,
+                    },
+                );
+                self.client_update(SubMsg::SetBookmarks(
+                    sub::SetBookmarks::new(id, &self.library[id]).into(),
+                ));
+            }
+            DataControlMsg::RemoveBookmark(name) => {
+                let Some(id) = self.player.now_playing() else {
+                    return Error::invalid_operation()
+                        .msg(format!("Cannot remove bookmark `{name}`."))
+                        .reason("There is no current song.")
+                        .err();
+                };
+                self.library.remove_bookmark(id, &name)?;
+                self.client_update(SubMsg::SetBookmarks(
+                    sub::SetBookmarks::new(id, &self.library[id]).into(),
+                ));
+            }
+            DataControlMsg::CreateNamedPlaylist(name) => {
+                self.library.create_playlist(name.clone())?;
+                self.client_update(SubMsg::SetNamedPlaylist(
+                    NamedPlaylist::new(name, vec![]).into(),
+                ));
+            }
+            DataControlMsg::AppendToNamedPlaylist(m) => {
+                let songs = m.query.get_ids(
+                    &self.library,
+                    self.config.sort_opts(),
+                    self.config.default_base().clone(),
+                    &self.player,
+                )?;
+                let songs =
+                    self.library.append_to_playlist(&m.name, &songs)?.to_vec();
+                self.client_update(SubMsg::SetNamedPlaylist(
+                    NamedPlaylist::new(m.name, songs).into(),
+                ));
+            }
+            DataControlMsg::RemoveFromNamedPlaylist(m) => {
+                let songs = m.query.get_ids(
+                    &self.library,
+                    self.config.sort_opts(),
+                    self.config.default_base().clone(),
+                    &self.player,
+                )?;
+                let songs = self
+                    .library
+                    .remove_from_playlist(&m.name, &songs)?
+                    .to_vec();
+                self.client_update(SubMsg::SetNamedPlaylist(
+                    NamedPlaylist::new(m.name, songs).into(),
+                ));
+            }
+            DataControlMsg::PushNamedPlaylist(name) => {
+                let Some(songs) = self.library.get_playlist(&name) else {
+                    return Error::invalid_operation()
+                        .msg(format!(
+                            "There is no playlist with the name `{name}`."
+                        ))
+                        .err();
+                };
+                let songs = songs.to_vec();
+                self.player.push_playlist(
+                    &mut self.library,
+                    songs.into(),
+                    false,
+                );
+                self.client_update_set_playlist(|p| {
+                    SubMsg::PushPlaylist(p.into())
+                });
+            }
+            DataControlMsg::DeleteNamedPlaylist(name) => {
+                self.library.delete_playlist(&name)?;
+                self.client_update(SubMsg::DeleteNamedPlaylist(name));
+            }
+            DataControlMsg::PushSmartPlaylist(name) => {
+                let Some(sp) = self.config.smart_playlists().get(&name) else {
+                    return Error::invalid_operation()
+                        .msg(format!("There is no smart playlist `{name}`."))
+                        .err();
+                };
+                let songs = sp.0.get_ids(
+                    &self.library,
+                    self.config.sort_opts(),
+                    self.config.default_base().clone(),
+                    &self.player,
+                )?;
+                let mut pl: Playlist = songs.into();
+                pl.smart = Some(name);
+                self.player.push_playlist(&mut self.library, pl, false);
+                self.client_update_set_playlist(|p| {
+                    SubMsg::PushPlaylist(p.into())
+                });
+            }
+            DataControlMsg::ImportPlaylist(path) => {
+                let paths = read_playlist_file(&path)?;
+                let ids = self.library.get_or_add_tmp_paths(&paths);
+                self.player
+                    .push_playlist(&mut self.library, ids.into(), true);
+                self.client_update_tmp_songs();
+            }
         }
 
         Ok(vec![])
@@ -332,6 +539,46 @@ impl FromStr for DataControlMsg {
             v if starts_any!(v, "remove-tag=", "untag=") => {
                 Ok(DataControlMsg::RemoveTag(val_arg(v, '=')?))
             }
+            v if starts_any!(v, "edit-songs=", "edit=") => {
+                Ok(DataControlMsg::EditSongs(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "rate=", "rating=") => {
+                Ok(DataControlMsg::RateSongs(val_arg(v, '=')?))
+            }
+            v if has_any_key!(v, '=', "clear-resume") => {
+                Ok(DataControlMsg::ClearResume(
+                    mval_arg(v, '=')?.unwrap_or_default(),
+                ))
+            }
+            v if has_any_key!(v, '=', "bookmark", "bm") => {
+                Ok(DataControlMsg::AddBookmark(mval_arg(v, '=')?))
+            }
+            v if starts_any!(v, "unbookmark=", "unbm=") => {
+                Ok(DataControlMsg::RemoveBookmark(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "pl-create=") => {
+                Ok(DataControlMsg::CreateNamedPlaylist(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "pl-append=") => {
+                Ok(DataControlMsg::AppendToNamedPlaylist(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "pl-remove=") => {
+                Ok(DataControlMsg::RemoveFromNamedPlaylist(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "pl-push=") => {
+                Ok(DataControlMsg::PushNamedPlaylist(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "pl-delete=") => {
+                Ok(DataControlMsg::DeleteNamedPlaylist(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "push-smart=", "smart=") => {
+                Ok(DataControlMsg::PushSmartPlaylist(val_arg(v, '=')?))
+            }
+            v if starts_any!(v, "import=") => {
+                Ok(DataControlMsg::ImportPlaylist(
+                    val_arg::<&Path>(v, '=')?.canonicalize()?,
+                ))
+            }
             v => ArgError::from_msg(
                 ArgErrKind::UnknownArgument,
                 "Unknown control msg.",
@@ -377,6 +624,29 @@ impl Display for DataControlMsg {
             }
             DataControlMsg::AddTag(m) => write!(f, "tag={m}"),
             DataControlMsg::RemoveTag(m) => write!(f, "untag={m}"),
+            DataControlMsg::EditSongs(m) => write!(f, "edit={m}"),
+            DataControlMsg::RateSongs(m) => write!(f, "rate={m}"),
+            DataControlMsg::ClearResume(q) => write!(f, "clear-resume={q}"),
+            DataControlMsg::AddBookmark(None) => f.write_str("bookmark"),
+            DataControlMsg::AddBookmark(Some(n)) => write!(f, "bookmark={n}"),
+            DataControlMsg::RemoveBookmark(n) => write!(f, "unbookmark={n}"),
+            DataControlMsg::CreateNamedPlaylist(n) => {
+                write!(f, "pl-create={n}")
+            }
+            DataControlMsg::AppendToNamedPlaylist(m) => {
+                write!(f, "pl-append={m}")
+            }
+            DataControlMsg::RemoveFromNamedPlaylist(m) => {
+                write!(f, "pl-remove={m}")
+            }
+            DataControlMsg::PushNamedPlaylist(n) => write!(f, "pl-push={n}"),
+            DataControlMsg::DeleteNamedPlaylist(n) => {
+                write!(f, "pl-delete={n}")
+            }
+            DataControlMsg::PushSmartPlaylist(n) => write!(f, "smart={n}"),
+            DataControlMsg::ImportPlaylist(p) => {
+                write!(f, "import={}", p.display())
+            }
         }
     }
 }
@@ -0,0 +1,55 @@
+use std::fmt::{Display, Write};
+
+use pareg::{ArgError, FromArg};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{library::SongEdit, query::Query};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditSongs {
+    /// Whether the changes should be written to the files. [`None`] means
+    /// that the value from configuration is used.
+    pub write: Option<bool>,
+    pub edit: SongEdit,
+    pub query: Query,
+}
+
+impl Display for EditSongs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.write {
+            Some(true) => f.write_char('+')?,
+            Some(false) => f.write_char('-')?,
+            _ => {}
+        }
+
+        write!(f, "{}:{}", self.edit, self.query)
+    }
+}
+
+impl<'a> FromArg<'a> for EditSongs {
+    fn from_arg(mut arg: &'a str) -> pareg::Result<Self> {
+        let mut write = None;
+        if let Some(a) = arg.strip_prefix('+') {
+            write = Some(true);
+            arg = a;
+        } else if let Some(a) = arg.strip_prefix('-') {
+            write = Some(false);
+            arg = a;
+        }
+
+        let (edit, Some(query)) = SongEdit::parse_prefix(arg)? else {
+            return ArgError::failed_to_parse(
+                "Missing query in song edit.",
+                arg,
+            )
+            .hint("Use `:` to separate the changes from the query.")
+            .err();
+        };
+
+        Ok(Self {
+            write,
+            edit,
+            query: query.parse()?,
+        })
+    }
+}
@@ -1,4 +1,9 @@
 mod add_tag;
+mod edit_songs;
+mod playlist_songs;
+mod rate_songs;
 mod remove_tag;
 
-pub use self::{add_tag::*, remove_tag::*};
+pub use self::{
+    add_tag::*, edit_songs::*, playlist_songs::*, rate_songs::*, remove_tag::*,
+};
@@ -0,0 +1,55 @@
+use std::fmt::Display;
+
+use pareg::{ArgError, FromArg};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{library::Song, query::Query};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateSongs {
+    /// The new rating. [`None`] removes the rating.
+    pub rating: Option<u8>,
+    /// Songs to rate. [`None`] means the current song.
+    pub query: Option<Query>,
+}
+
+impl Display for RateSongs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(r) = self.rating {
+            write!(f, "{r}")?;
+        }
+        if let Some(q) = &self.query {
+            write!(f, ":{q}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> FromArg<'a> for RateSongs {
+    fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+        let (rating, query) = match arg.split_once(':') {
+            Some((r, q)) => (r, Some(q)),
+            None => (arg, None),
+        };
+
+        let rating = if rating.is_empty() {
+            None
+        } else {
+            let r = u8::from_arg(rating)?;
+            if r > Song::MAX_RATING {
+                return ArgError::failed_to_parse(
+                    format!("Rating must be at most {}.", Song::MAX_RATING),
+                    arg,
+                )
+                .spanned(0..rating.len())
+                .err();
+            }
+            Some(r)
+        };
+
+        Ok(Self {
+            rating,
+            query: query.map(|q| q.parse()).transpose()?,
+        })
+    }
+}
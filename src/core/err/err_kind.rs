@@ -49,6 +49,12 @@ pub enum ErrKind {
     /// the audiotags library error.
     #[error(transparent)]
     Ratag(#[from] ratag::Error),
+    /// The lofty library failed to read tags.
+    #[error(transparent)]
+    LoftyParse(#[from] lofty::error::FileParseError),
+    /// The lofty library failed to write tags.
+    #[error(transparent)]
+    LoftyEncode(#[from] lofty::error::FileEncodingError),
     /// The raplay library returned error.
     #[error(transparent)]
     Raplay(#[from] raplay::Error),
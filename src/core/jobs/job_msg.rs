@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use crate::core::{
     AppCtrl, Job, Msg, Result, UampApp,
     library::{LibraryLoadResult, SongId},
@@ -19,7 +21,8 @@ impl UampApp {
     ) -> Result<Vec<Msg>> {
         match msg {
             JobMsg::LibraryLoad(res) => {
-                self.finish_library_load(ctrl, res?)?
+                self.finish_library_load(ctrl, res?)?;
+                self.library_files_changed(ctrl, BTreeSet::new())?;
             }
             JobMsg::LibrarySave(res) => self.finish_library_save_songs(res)?,
             JobMsg::Server(Err(e)) => {
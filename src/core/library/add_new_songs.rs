@@ -2,14 +2,23 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::{self, DirEntry, FileType},
     mem,
-    path::PathBuf,
-    sync::Arc,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
 };
 
+use itertools::Itertools;
+
 use crate::core::{
     LogResult, Result,
     config::Config,
-    library::{Album, AlbumId, Albums, Artist, ArtistId, Artists, tags::Tags},
+    library::{
+        Album, AlbumId, Albums, Artist, ArtistId, Artists, CueSheet,
+        tags::Tags,
+    },
 };
 
 use super::{LibraryLoadResult, Song, SongId};
@@ -18,18 +27,39 @@ use super::{LibraryLoadResult, Song, SongId};
 //                                   Public                                  //
 //===========================================================================//
 
+/// Callback that receives the number of loaded songs and the total number of
+/// songs that will be loaded.
+pub(super) type Progress = dyn Fn(usize, usize) + Sync;
+
 /// Adds new songs to the given vector of songs
 pub(super) fn add_new_songs(
     res: &mut LibraryLoadResult,
     conf: &Config,
     remove_missing: bool,
+    progress: &Progress,
 ) {
-    let mut state = State::new(res, conf, remove_missing);
+    let mut state = State::new(res, conf, remove_missing, progress);
     state.init();
     state.load();
     state.finalize();
 }
 
+/// Applies changes of the given files and directories to the songs. New
+/// songs are added, modified songs are reloaded and songs that no longer
+/// exist are removed.
+pub(super) fn apply_file_changes(
+    res: &mut LibraryLoadResult,
+    conf: &Config,
+    changes: BTreeSet<PathBuf>,
+) {
+    let mut state = State::new(res, conf, true, &|_, _| {});
+    state.paths.clear();
+    state.init_changes();
+    state.apply_changes(changes);
+    state.load();
+    state.finalize();
+}
+
 pub(super) fn construct_album_artists(
     songs: &mut [Song],
 ) -> (Albums, Artists) {
@@ -46,7 +76,7 @@ pub(super) fn construct_album_artists(
     }
 
     for album in albums.values_mut() {
-        normalize_album(album, songs);
+        album.normalize(songs);
     }
 
     for artist in artists.values_mut() {
@@ -56,12 +86,99 @@ pub(super) fn construct_album_artists(
     (albums, artists)
 }
 
+/// Checks whether the path is path to cue sheet.
+pub(super) fn is_cue_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("cue"))
+}
+
+/// Adds the ids of the album and artists of the song to the given sets.
+pub(super) fn album_artist_ids(
+    song: &Song,
+    albums: &mut BTreeSet<AlbumId>,
+    artists: &mut BTreeSet<ArtistId>,
+) {
+    if let Some(aa) = song.album_artist()
+        && let Some(album) = song.album()
+    {
+        albums.insert(AlbumId::new(aa, album));
+    }
+    artists.extend(song.album_artist().map(ArtistId::new));
+    artists.extend(song.artists().iter().map(ArtistId::new));
+    artists.extend(song.composers().iter().map(ArtistId::new));
+}
+
+/// Updates albums and artists after the metadata of the given songs has
+/// changed. `mod_albums` and `mod_artists` must contain the albums and
+/// artists of the songs before the change. Other albums and artists are not
+/// touched.
+pub(super) fn update_album_artists(
+    songs: &mut [Song],
+    ids: &[SongId],
+    albums: &mut Albums,
+    artists: &mut Artists,
+    mut mod_albums: BTreeSet<AlbumId>,
+    mut mod_artists: BTreeSet<ArtistId>,
+) {
+    for id in ids {
+        album_artist_ids(
+            &songs[id.as_norm()],
+            &mut mod_albums,
+            &mut mod_artists,
+        );
+    }
+
+    // Remove the songs from their old albums and artists.
+    for a in &mod_albums {
+        if let Some(alb) = albums.get_mut(a) {
+            alb.songs.retain(|s| !ids.contains(s));
+        }
+    }
+    for a in &mod_artists {
+        if let Some(art) = artists.get_mut(a) {
+            art.singles.retain(|s| !ids.contains(s));
+            art.compositions.retain(|s| !ids.contains(s));
+        }
+    }
+
+    for id in ids {
+        add_song_album_artists(&mut songs[id.as_norm()], *id, albums, artists);
+    }
+
+    for a in &mod_albums {
+        let Some(alb) = albums.get_mut(a) else {
+            continue;
+        };
+        if !alb.songs.is_empty() {
+            alb.normalize(songs);
+            continue;
+        }
+        let alb = albums.remove(a).unwrap();
+        if let Some(art) = artists.get_mut(&ArtistId::new(&alb.artist)) {
+            art.albums.retain(|i| i != a);
+        }
+    }
+
+    for a in &mod_artists {
+        let Some(art) = artists.get_mut(a) else {
+            continue;
+        };
+        if art.is_empty() {
+            artists.remove(a);
+        } else {
+            normalize_artist(art, songs);
+        }
+    }
+}
+
 //===========================================================================//
 //                                  Private                                  //
 //===========================================================================//
 
 struct State<'a> {
     conf: &'a Config,
+    // Reports the number of loaded songs out of the total.
+    progress: &'a Progress,
     remove_missing: bool,
     // All the songs in the library, this is the main result.
     songs: &'a mut Vec<Song>,
@@ -77,20 +194,41 @@ struct State<'a> {
     first_new: &'a mut usize,
     // True if some songs were removed from `songs`.
     any_removed: &'a mut bool,
+    // True if some songs were reloaded or their file stamps were filled.
+    modified: &'a mut bool,
     // Directories to visit.
     paths: BTreeSet<PathBuf>,
     // Already visited directories.
     searched: HashSet<PathBuf>,
     // Indexes of all deleted songs in `songs`.
     empty: Vec<usize>,
-    // Maps song path to its id.
-    id_map: HashMap<PathBuf, usize>,
+    // Maps paths of files to ids of their songs.
+    id_map: IdMap,
     // Names of modified albums that should be normalized.
     modified_albums: BTreeSet<AlbumId>,
     // Names of modifed artists that should be normalized.
     modified_artists: BTreeSet<ArtistId>,
 }
 
+/// Maps paths of files to ids of the songs loaded from them.
+#[derive(Default)]
+struct IdMap {
+    // Maps path of audio file to its songs. Audio files split by cue sheets
+    // have multiple songs.
+    files: HashMap<PathBuf, Vec<usize>>,
+    // Maps path of cue sheet to its songs.
+    sheets: HashMap<PathBuf, Vec<usize>>,
+}
+
+/// File from which songs are loaded.
+enum SongFile {
+    Audio(PathBuf),
+    Cue(PathBuf, CueSheet),
+}
+
+/// Progress is reported after this many songs are loaded.
+const PROGRESS_STEP: usize = 256;
+
 macro_rules! err_cont {
     ($e:expr, $err:ident => $msg:expr $(,)?) => {
         match $e.or_log_with(log::Level::Error, || $msg) {
@@ -105,6 +243,7 @@ impl<'a> State<'a> {
         res: &'a mut LibraryLoadResult,
         conf: &'a Config,
         remove_missing: bool,
+        progress: &'a Progress,
     ) -> Self {
         let paths = conf
             .search_paths()
@@ -114,6 +253,7 @@ impl<'a> State<'a> {
 
         Self {
             conf,
+            progress,
             remove_missing,
             songs: &mut res.songs,
             albums: &mut res.albums,
@@ -122,10 +262,11 @@ impl<'a> State<'a> {
             sparse_new: &mut res.sparse_new,
             first_new: &mut res.first_new,
             any_removed: &mut res.removed,
+            modified: &mut res.modified,
             paths,
             searched: HashSet::new(),
             empty: vec![],
-            id_map: HashMap::new(),
+            id_map: IdMap::default(),
             modified_albums: BTreeSet::new(),
             modified_artists: BTreeSet::new(),
         }
@@ -143,10 +284,13 @@ impl<'a> State<'a> {
 
         *self.first_new = self.songs.len();
 
-        self.map_songs(&mut removed);
+        let mut changed = vec![];
+        self.map_songs(&mut removed, &mut changed);
 
         *self.any_removed = !removed.is_empty();
+        let (reloaded, new) = self.reload_songs(changed, &mut removed);
         self.propagate_remove(removed);
+        self.add_reloaded(reloaded, new);
     }
 
     /// pops all invalid songs (deleted == true) and songs that don't exist
@@ -158,7 +302,7 @@ impl<'a> State<'a> {
                 continue;
             }
 
-            if s.path().exists() {
+            if s.exists() {
                 break;
             }
 
@@ -175,8 +319,13 @@ impl<'a> State<'a> {
         }
     }
 
-    fn map_songs(&mut self, removed: &mut Vec<(SongId, Song)>) {
-        // fill `empty` and `songs`, and remove non existing songs
+    fn map_songs(
+        &mut self,
+        removed: &mut Vec<(SongId, Song)>,
+        changed: &mut Vec<usize>,
+    ) {
+        // fill `empty` and `songs`, remove non existing songs and find songs
+        // with changed files
         for (i, s) in self.songs.iter_mut().enumerate() {
             // sparse deleted song
             if s.is_deleted() {
@@ -186,9 +335,18 @@ impl<'a> State<'a> {
 
             match s.path().canonicalize() {
                 // song does exist
-                Ok(path) => self.id_map.insert(path, i),
+                Ok(path) if s.exists() => {
+                    // Without stamp, it is not known whether the file
+                    // changed. Don't reload all songs from older versions.
+                    if s.fill_file_stamp() {
+                        *self.modified = true;
+                    } else if s.file_changed() {
+                        changed.push(i);
+                    }
+                    self.id_map.insert(path, s, i)
+                }
                 // song doesn't exist
-                Err(_) => {
+                _ => {
                     if self.remove_missing {
                         *self.any_removed = true;
                         removed.push((
@@ -203,12 +361,235 @@ impl<'a> State<'a> {
         }
     }
 
+    /// Same as [`Self::init`] but doesn't check whether songs exist.
+    fn init_changes(&mut self) {
+        self.pop_deleted();
+        *self.first_new = self.songs.len();
+
+        for (i, s) in self.songs.iter().enumerate() {
+            if s.is_deleted() {
+                self.empty.push(i);
+            } else {
+                self.id_map.insert(s.path().to_owned(), s, i);
+            }
+        }
+    }
+
+    fn apply_changes(&mut self, changes: BTreeSet<PathBuf>) {
+        let mut removed = BTreeSet::new();
+        let mut changed = vec![];
+        let mut added = vec![];
+        let mut sheets = vec![];
+
+        for path in changes {
+            if path.is_dir() {
+                if self.conf.recursive_search()
+                    || self.conf.search_paths().contains(&path)
+                {
+                    self.paths.insert(path);
+                }
+            } else if self.id_map.contains(&path) {
+                let ids = self.id_map.get(&path);
+                if !path.exists() {
+                    removed.extend(ids);
+                } else {
+                    changed.extend(ids.iter().filter(|i| {
+                        let s = &self.songs[**i];
+                        !s.has_file_stamp() || s.file_changed()
+                    }));
+                }
+            } else if path.exists() {
+                if self.is_audio_file(&path) {
+                    added.push(path);
+                } else if is_cue_file(&path) {
+                    sheets.push(path);
+                }
+            } else {
+                // Removed directory.
+                removed.extend(self.id_map.under(&path));
+            }
+        }
+        changed.sort();
+        changed.dedup();
+
+        let mut old = vec![];
+        for i in removed {
+            self.remove_song(i, &mut old);
+        }
+
+        let (reloaded, new) = self.reload_songs(changed, &mut old);
+        self.propagate_remove(old);
+        self.add_reloaded(reloaded, new);
+
+        for path in sheets {
+            err_cont!(
+                self.add_cue_sheet(path.clone()),
+                err => format!("Failed to add cue sheet `{}`", path.display()),
+            );
+        }
+
+        for path in added {
+            // The file may be split by one of the new cue sheets.
+            if self.id_map.contains(&path) {
+                continue;
+            }
+            err_cont!(
+                self.add_song(path.clone()),
+                err => format!("Failed to add song `{}`", path.display()),
+            );
+        }
+    }
+
+    /// Removes the song at the given index. The old song is added to `old` so
+    /// that it can be removed from albums and artists.
+    fn remove_song(&mut self, idx: usize, old: &mut Vec<(SongId, Song)>) {
+        let s = mem::replace(&mut self.songs[idx], Song::invalid());
+        self.id_map.remove(&s, idx);
+        old.push((SongId::norm(idx), s));
+        self.empty.push(idx);
+        *self.any_removed = true;
+    }
+
+    /// Reloads the songs at the given indexes in place. Old versions of the
+    /// songs are added to `old` so that they can be removed from albums and
+    /// artists. Returns indexes of the reloaded songs and new songs from
+    /// the cue sheets of the reloaded songs.
+    fn reload_songs(
+        &mut self,
+        idxs: Vec<usize>,
+        old: &mut Vec<(SongId, Song)>,
+    ) -> (Vec<usize>, Vec<Song>) {
+        let mut res = vec![];
+        let mut sheets = BTreeSet::new();
+        // Songs from cue sheets are reloaded together.
+        let idxs = idxs
+            .into_iter()
+            .filter(|i| match self.songs[*i].range() {
+                Some(r) => {
+                    sheets.insert(r.sheet.clone());
+                    false
+                }
+                None => true,
+            })
+            .collect_vec();
+
+        let songs = &*self.songs;
+        let reloaded =
+            self.par_map_progress(&idxs, self.thread_count(), |i| {
+                songs[*i].reload()
+            });
+        for (i, song) in idxs.into_iter().zip(reloaded) {
+            let song = err_cont!(
+                song,
+                err => format!(
+                    "Failed to reload song `{}`",
+                    self.songs[i].path().display()
+                ),
+            );
+            let mut s = mem::replace(&mut self.songs[i], song);
+            // Tags are kept by the reloaded song.
+            s.tags.clear();
+            old.push((SongId::norm(i), s));
+            res.push(i);
+        }
+
+        let mut new = vec![];
+        for sheet in sheets {
+            new.extend(self.reload_cue_sheet(&sheet, old, &mut res));
+        }
+
+        *self.modified |= !res.is_empty();
+        (res, new)
+    }
+
+    /// Reloads songs of the cue sheet in place. Songs of tracks that are no
+    /// longer in the sheet are removed. Returns songs of tracks that are new
+    /// in the sheet.
+    fn reload_cue_sheet(
+        &mut self,
+        sheet: &Path,
+        old: &mut Vec<(SongId, Song)>,
+        reloaded: &mut Vec<usize>,
+    ) -> Vec<Song> {
+        let ids = self.id_map.get(sheet).to_vec();
+        let Some(mut new) = CueSheet::load(sheet)
+            .and_then(|c| Song::from_cue(sheet, &c, self.auto_tags()))
+            .or_log_with(log::Level::Error, || {
+                format!("Failed to reload cue sheet `{}`", sheet.display())
+            })
+        else {
+            return vec![];
+        };
+
+        for i in ids {
+            let track = self.songs[i].range().map(|r| r.track);
+            let Some(pos) =
+                new.iter().position(|s| s.range().map(|r| r.track) == track)
+            else {
+                self.remove_song(i, old);
+                continue;
+            };
+
+            let mut song = new.remove(pos);
+            song.inherit(&self.songs[i]);
+            let mut s = mem::replace(&mut self.songs[i], song);
+            // Path to the audio file may have changed.
+            self.id_map.remove(&s, i);
+            self.id_map.insert(
+                self.songs[i].path().to_owned(),
+                &self.songs[i],
+                i,
+            );
+            // Tags are kept by the reloaded song.
+            s.tags.clear();
+            old.push((SongId::norm(i), s));
+            reloaded.push(i);
+        }
+
+        new
+    }
+
+    /// Adds the reloaded songs to albums and artists and inserts the new
+    /// songs.
+    fn add_reloaded(&mut self, idxs: Vec<usize>, new: Vec<Song>) {
+        for i in idxs {
+            add_song_album_artists(
+                &mut self.songs[i],
+                SongId::norm(i),
+                self.albums,
+                self.artists,
+            );
+            self.mark_modified(i);
+        }
+
+        for s in new {
+            self.insert_song(s);
+        }
+    }
+
+    /// Marks the album and artists of the song so that they are normalized.
+    fn mark_modified(&mut self, idx: usize) {
+        album_artist_ids(
+            &self.songs[idx],
+            &mut self.modified_albums,
+            &mut self.modified_artists,
+        );
+    }
+
     fn propagate_remove(&mut self, removed: Vec<(SongId, Song)>) {
         let mut rem_alb: BTreeMap<AlbumId, Vec<SongId>> = BTreeMap::new();
         let mut rem_singles: BTreeMap<ArtistId, Vec<SongId>> = BTreeMap::new();
+        let mut rem_comp: BTreeMap<ArtistId, Vec<SongId>> = BTreeMap::new();
         let mut rem_tags: BTreeMap<Arc<str>, HashSet<SongId>> =
             BTreeMap::new();
         for (id, mut s) in removed {
+            for c in mem::take(&mut s.composers) {
+                rem_comp.entry(ArtistId::new(c)).or_default().push(id);
+            }
+            for t in mem::take(&mut s.tags) {
+                rem_tags.entry(t).or_default().insert(id);
+            }
+
             let Some(album) = s.album else {
                 for a in s.artists {
                     rem_singles.entry(ArtistId::new(a)).or_default().push(id);
@@ -235,13 +616,11 @@ impl<'a> State<'a> {
             for a in s.artists {
                 rem_singles.entry(ArtistId::new(a)).or_default().push(id);
             }
-            for t in s.tags {
-                rem_tags.entry(t).or_default().insert(id);
-            }
         }
 
         let rem_art = self.remove_from_albums(rem_alb);
         self.remove_from_singles(rem_singles);
+        self.remove_from_compositions(rem_comp);
         self.remove_from_artists(rem_art);
         self.remove_from_tags(rem_tags);
     }
@@ -271,6 +650,8 @@ impl<'a> State<'a> {
                     .entry(ArtistId::new(&alb.artist))
                     .or_default()
                     .push(AlbumId::new(alb.artist, alb.name));
+            } else {
+                self.modified_albums.insert(key);
             }
         }
 
@@ -295,7 +676,24 @@ impl<'a> State<'a> {
                 }
             });
 
-            if art.singles.is_empty() && art.albums.is_empty() {
+            if art.is_empty() {
+                self.artists.remove(&key);
+            }
+        }
+    }
+
+    fn remove_from_compositions(
+        &mut self,
+        rem_comp: impl IntoIterator<Item = (ArtistId, Vec<SongId>)>,
+    ) {
+        for (key, songs) in rem_comp {
+            let Some(art) = self.artists.get_mut(&key) else {
+                continue;
+            };
+
+            art.compositions.retain(|a| !songs.contains(a));
+
+            if art.is_empty() {
                 self.artists.remove(&key);
             }
         }
@@ -319,7 +717,7 @@ impl<'a> State<'a> {
                 }
             });
 
-            if art.singles.is_empty() && art.albums.is_empty() {
+            if art.is_empty() {
                 self.artists.remove(&key);
             }
         }
@@ -342,86 +740,217 @@ impl<'a> State<'a> {
     }
 
     fn load(&mut self) {
-        // go trough all the directories
-        while let Some(path) = self.paths.pop_first() {
-            // open directory
-            let dir = err_cont!(
-                fs::read_dir(&path),
-                err => format!("Failed to open directory `{:?}`", path.display()),
-            );
+        let threads = self.thread_count();
+        let files = self.walk(threads);
+        let files = self.read_cue_sheets(files);
+        self.read_songs(files, threads);
+    }
 
-            self.searched.insert(path);
+    /// Gets the number of threads used for loading.
+    fn thread_count(&self) -> usize {
+        match self.conf.scan_threads() {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
 
-            // go trough all the entries in the directory
-            for f in dir {
-                let file = err_cont!(
-                    f,
-                    err => "failed to get directory entry",
+    /// Goes trough all the directories and collects paths to new songs and
+    /// cue sheets. The result is sorted.
+    fn walk(&mut self, threads: usize) -> Vec<PathBuf> {
+        let mut files = vec![];
+
+        // Directories at the same depth are read in parallel.
+        while !self.paths.is_empty() {
+            let dirs = mem::take(&mut self.paths)
+                .into_iter()
+                .filter(|p| self.searched.insert(p.clone()))
+                .collect_vec();
+            let entries = par_map(&dirs, threads, |p| read_entries(p));
+
+            for (path, dir) in dirs.iter().zip(entries) {
+                let dir = err_cont!(
+                    dir,
+                    err => format!("Failed to open directory `{:?}`", path.display()),
                 );
 
-                err_cont!(
-                    self.add_file(file),
-                    err => "failed to examine entry",
-                );
+                // go trough all the entries in the directory
+                for f in dir {
+                    let (ftype, path) = err_cont!(
+                        f,
+                        err => "failed to examine entry",
+                    );
+                    self.add_file(ftype, path, &mut files);
+                }
             }
         }
+
+        files.sort();
+        files.dedup();
+        files
     }
 
     /// Add file/subdirectory
-    fn add_file(&mut self, f: DirEntry) -> Result<()> {
-        let (ftype, path) = Self::resolve_symlink(f)?;
-
+    fn add_file(
+        &mut self,
+        ftype: FileType,
+        path: PathBuf,
+        files: &mut Vec<PathBuf>,
+    ) {
         // Check directories
         if ftype.is_dir() {
             if self.conf.recursive_search() && !self.searched.contains(&path) {
                 self.paths.insert(path);
             }
-            return Ok(());
+            return;
         }
 
-        let Some(fe) = path.extension() else {
-            return Ok(());
-        };
-
-        // Filter by extension
-        if !self
-            .conf
-            .audio_extensions()
-            .iter()
-            .any(|e| fe == e.as_str())
+        // Check if it is audio file or cue sheet that is not already in
+        // library.
+        if (self.is_audio_file(&path) || is_cue_file(&path))
+            && !self.id_map.contains(&path)
         {
-            return Ok(());
+            files.push(path);
         }
+    }
 
-        // Check if it is already in library.
-        if self.id_map.contains_key(&path) {
-            return Ok(());
+    /// Reads the cue sheets among the paths. Audio files that are split by
+    /// the cue sheets are replaced by the cue sheets.
+    fn read_cue_sheets(&mut self, files: Vec<PathBuf>) -> Vec<SongFile> {
+        let (sheets, files): (Vec<_>, Vec<_>) =
+            files.into_iter().partition(|p| is_cue_file(p));
+
+        let mut res = vec![];
+        let mut split = HashSet::new();
+        for path in sheets {
+            let sheet = err_cont!(
+                CueSheet::load(&path),
+                err => format!(
+                    "Failed to read cue sheet `{}`",
+                    path.display()
+                ),
+            );
+            self.remove_split(&sheet);
+            split.extend(sheet.files.iter().map(|f| f.path.clone()));
+            res.push(SongFile::Cue(path, sheet));
         }
 
-        self.add_song(path)
+        // Paths of files in cue sheets are canonical, but the paths from
+        // walk may not be.
+        let names: HashSet<_> =
+            split.iter().filter_map(|p| p.file_name()).collect();
+        let is_split = |p: &Path| {
+            split.contains(p)
+                || p.file_name().is_some_and(|n| names.contains(n))
+                    && p.canonicalize().is_ok_and(|p| split.contains(&p))
+        };
+        res.extend(
+            files
+                .into_iter()
+                .filter(|p| !is_split(p))
+                .map(SongFile::Audio),
+        );
+        res.sort_by(|a, b| a.path().cmp(b.path()));
+        res
     }
 
-    /// Gets the file type and canonical path.
-    fn resolve_symlink(f: DirEntry) -> Result<(FileType, PathBuf)> {
-        let ftype = f.file_type()?;
+    /// Removes songs of whole audio files that are split by the cue sheet.
+    fn remove_split(&mut self, sheet: &CueSheet) {
+        let mut old = vec![];
+        for f in &sheet.files {
+            for i in self.id_map.get_canonical(&f.path) {
+                if self.songs[i].range().is_none() {
+                    self.remove_song(i, &mut old);
+                }
+            }
+        }
+        self.propagate_remove(old);
+    }
 
-        if ftype.is_symlink() {
-            let path = f.path().canonicalize()?;
-            Ok((path.metadata()?.file_type(), path))
-        } else {
-            Ok((ftype, f.path()))
+    /// Reads the songs in parallel and adds them to the library in the order
+    /// of the paths.
+    fn read_songs(&mut self, files: Vec<SongFile>, threads: usize) {
+        let tags = self.auto_tags();
+        let songs =
+            self.par_map_progress(&files, threads, |f| f.load(tags.clone()));
+
+        for (file, songs) in files.into_iter().zip(songs) {
+            let songs = err_cont!(
+                songs,
+                err => format!(
+                    "Failed to load song `{}`",
+                    file.path().display()
+                ),
+            );
+            for s in songs {
+                self.insert_song(s);
+            }
         }
     }
 
-    fn add_song(&mut self, p: PathBuf) -> Result<()> {
-        let tags = self
-            .conf
+    /// Maps the items on the given number of threads and reports the
+    /// progress.
+    fn par_map_progress<T: Sync, R: Send>(
+        &self,
+        items: &[T],
+        threads: usize,
+        f: impl Fn(&T) -> R + Sync,
+    ) -> Vec<R> {
+        let total = items.len();
+        if total == 0 {
+            return vec![];
+        }
+
+        let done = AtomicUsize::new(0);
+        let progress = self.progress;
+        let res = par_map(items, threads, |i| {
+            let res = f(i);
+            let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+            if done.is_multiple_of(PROGRESS_STEP) {
+                progress(done, total);
+            }
+            res
+        });
+
+        progress(total, total);
+        res
+    }
+
+    /// Gets the names of the tags that are automatically added to new songs.
+    fn auto_tags(&self) -> BTreeSet<Arc<str>> {
+        self.conf
             .auto_tags()
             .iter()
             .map(|a| a.name.clone())
-            .collect();
-        let song = Song::from_path(p, tags)?;
+            .collect()
+    }
+
+    /// Filter by extension.
+    fn is_audio_file(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|fe| {
+            self.conf
+                .audio_extensions()
+                .iter()
+                .any(|e| fe == e.as_str())
+        })
+    }
+
+    fn add_song(&mut self, p: PathBuf) -> Result<()> {
+        let song = Song::from_path(&p, self.auto_tags())?;
+        self.insert_song(song);
+        Ok(())
+    }
 
+    fn add_cue_sheet(&mut self, p: PathBuf) -> Result<()> {
+        let sheet = CueSheet::load(&p)?;
+        let songs = Song::from_cue(&p, &sheet, self.auto_tags())?;
+        self.remove_split(&sheet);
+        for s in songs {
+            self.insert_song(s);
+        }
+        Ok(())
+    }
+
+    fn insert_song(&mut self, song: Song) {
         // Assign id to the song by inserting it to the library.
         let id = if let Some(i) = self.empty.pop() {
             self.songs[i] = song;
@@ -433,6 +962,12 @@ impl<'a> State<'a> {
             self.songs.push(song);
             id
         };
+        let idx = id.as_norm();
+        self.id_map.insert(
+            self.songs[idx].path().to_owned(),
+            &self.songs[idx],
+            idx,
+        );
 
         for t in self.conf.auto_tags() {
             let t = self
@@ -446,8 +981,7 @@ impl<'a> State<'a> {
         let song = &mut self.songs[id.as_norm()];
 
         add_song_album_artists(song, id, self.albums, self.artists);
-
-        Ok(())
+        self.mark_modified(id.as_norm());
     }
 
     fn finalize(&mut self) {
@@ -458,7 +992,7 @@ impl<'a> State<'a> {
     fn normalize_albums(&mut self) {
         for album in &self.modified_albums {
             if let Some(album) = self.albums.get_mut(album) {
-                normalize_album(album, self.songs);
+                album.normalize(self.songs);
             }
         }
     }
@@ -472,12 +1006,152 @@ impl<'a> State<'a> {
     }
 }
 
+impl IdMap {
+    fn insert(&mut self, path: PathBuf, song: &Song, idx: usize) {
+        self.files.entry(path).or_default().push(idx);
+        if let Some(r) = song.range() {
+            self.sheets.entry(r.sheet.clone()).or_default().push(idx);
+        }
+    }
+
+    fn remove(&mut self, song: &Song, idx: usize) {
+        remove_id(&mut self.files, song.path(), idx);
+        if let Some(r) = song.range() {
+            remove_id(&mut self.sheets, &r.sheet, idx);
+        }
+    }
+
+    /// Checks whether there are songs from the given audio file or cue
+    /// sheet.
+    fn contains(&self, path: &Path) -> bool {
+        self.files.contains_key(path) || self.sheets.contains_key(path)
+    }
+
+    /// Gets the songs from the given audio file or cue sheet.
+    fn get(&self, path: &Path) -> &[usize] {
+        self.files
+            .get(path)
+            .or_else(|| self.sheets.get(path))
+            .map_or(&[], |i| i)
+    }
+
+    /// Gets the songs of the audio file with the given canonical path. The
+    /// paths in the map don't have to be canonical.
+    fn get_canonical(&self, path: &Path) -> Vec<usize> {
+        let mut res = self.get(path).to_vec();
+        for (p, ids) in &self.files {
+            if p != path
+                && p.file_name() == path.file_name()
+                && p.canonicalize().is_ok_and(|p| p == path)
+            {
+                res.extend(ids);
+            }
+        }
+        res
+    }
+
+    /// Gets the songs with files in the given directory.
+    fn under<'a>(&'a self, dir: &'a Path) -> impl Iterator<Item = usize> + 'a {
+        self.files
+            .iter()
+            .chain(&self.sheets)
+            .filter(move |(p, _)| p.starts_with(dir))
+            .flat_map(|(_, i)| i.iter().copied())
+    }
+}
+
+impl SongFile {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Audio(p) | Self::Cue(p, _) => p,
+        }
+    }
+
+    fn load(&self, tags: BTreeSet<Arc<str>>) -> Result<Vec<Song>> {
+        match self {
+            Self::Audio(p) => Ok(vec![Song::from_path(p, tags)?]),
+            Self::Cue(p, sheet) => Song::from_cue(p, sheet, tags),
+        }
+    }
+}
+
+fn remove_id(map: &mut HashMap<PathBuf, Vec<usize>>, path: &Path, idx: usize) {
+    if let Some(ids) = map.get_mut(path) {
+        ids.retain(|i| *i != idx);
+        if ids.is_empty() {
+            map.remove(path);
+        }
+    }
+}
+
+/// Reads the entries of the directory.
+fn read_entries(p: &Path) -> Result<Vec<Result<(FileType, PathBuf)>>> {
+    Ok(fs::read_dir(p)?.map(|f| resolve_symlink(f?)).collect())
+}
+
+/// Gets the file type and canonical path.
+fn resolve_symlink(f: DirEntry) -> Result<(FileType, PathBuf)> {
+    let ftype = f.file_type()?;
+
+    if ftype.is_symlink() {
+        let path = f.path().canonicalize()?;
+        Ok((path.metadata()?.file_type(), path))
+    } else {
+        Ok((ftype, f.path()))
+    }
+}
+
+/// Maps the items on the given number of threads. The results are in the
+/// same order as the items.
+fn par_map<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut res: Vec<_> = thread::scope(|s| {
+        let workers = (0..threads.min(items.len()))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut res = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else {
+                            return res;
+                        };
+                        res.push((i, f(item)));
+                    }
+                })
+            })
+            .collect_vec();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("Library load thread panicked."))
+            .collect()
+    });
+
+    res.sort_unstable_by_key(|(i, _)| *i);
+    res.into_iter().map(|(_, r)| r).collect()
+}
+
 fn add_song_album_artists(
     song: &mut Song,
     id: SongId,
     albums: &mut Albums,
     artists: &mut Artists,
 ) {
+    for comp in &mut song.composers {
+        let artist = artists
+            .entry(ArtistId::new(&comp))
+            .or_insert_with(|| Artist::new(comp.clone()));
+        *comp = artist.name.clone();
+        artist.compositions.push(id);
+    }
+
     let Some(album_artist) = song.album_artist_arc() else {
         // Non existance of album artist implies that artists is empty.
         return;
@@ -518,11 +1192,11 @@ fn add_song_album_artists(
     }
 }
 
-fn normalize_album(album: &mut Album, songs: &[Song]) {
-    album.songs.sort_by_key(|id| songs[id.as_norm()].track());
-}
-
 fn normalize_artist(artist: &mut Artist, songs: &[Song]) {
     artist.albums.sort();
     artist.singles.sort_by_key(|id| songs[id.as_norm()].title());
+    artist.compositions.sort_by_key(|id| {
+        let s = &songs[id.as_norm()];
+        (s.album(), s.disc(), s.track(), s.title())
+    });
 }
@@ -1,13 +1,30 @@
 use std::sync::Arc;
 
+use itertools::Itertools;
 use serde::Serialize;
 
-use crate::core::library::SongId;
+use crate::core::library::{Song, SongId};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Album {
     pub(super) artist: Arc<str>,
     pub(super) name: Arc<str>,
+    /// All songs in the album ordered by disc and track.
+    pub(super) songs: Vec<SongId>,
+    /// Songs of the album split by disc.
+    pub(super) discs: Vec<Disc>,
+    /// Total number of discs in the album.
+    pub(super) disc_count: u32,
+}
+
+/// Single disc of an album.
+#[derive(Debug, Clone, Serialize)]
+pub struct Disc {
+    /// Number of the disc. [`None`] for songs without disc number.
+    pub(super) number: Option<u32>,
+    /// Subtitle of the disc if it is tagged.
+    pub(super) subtitle: Option<Arc<str>>,
+    /// Songs on the disc ordered by track.
     pub(super) songs: Vec<SongId>,
 }
 
@@ -17,6 +34,8 @@ impl Album {
             artist,
             name,
             songs: vec![],
+            discs: vec![],
+            disc_count: 0,
         }
     }
 
@@ -31,4 +50,62 @@ impl Album {
     pub fn artist(&self) -> &str {
         &self.artist
     }
+
+    /// Gets the songs of the album split by disc.
+    pub fn discs(&self) -> &[Disc] {
+        &self.discs
+    }
+
+    /// Gets the total number of discs in the album. This may be more than
+    /// the number of discs if some discs are missing.
+    pub fn disc_count(&self) -> u32 {
+        self.disc_count
+    }
+
+    /// Sorts the songs by disc and track and splits them into discs.
+    pub(super) fn normalize(&mut self, songs: &[Song]) {
+        let song = |id: &SongId| &songs[id.as_norm()];
+        self.songs
+            .sort_by_key(|s| (song(s).disc(), song(s).track()));
+
+        self.discs = self
+            .songs
+            .iter()
+            .chunk_by(|s| song(s).disc())
+            .into_iter()
+            .map(|(number, ids)| {
+                let songs = ids.copied().collect_vec();
+                let subtitle =
+                    songs.iter().find_map(|s| song(s).disc_subtitle.clone());
+                Disc {
+                    number,
+                    subtitle,
+                    songs,
+                }
+            })
+            .collect();
+
+        let tagged = self.songs.iter().filter_map(|s| {
+            let s = song(s);
+            s.disc_count().max(s.disc())
+        });
+        self.disc_count = tagged
+            .max()
+            .unwrap_or_default()
+            .max(self.discs.len() as u32);
+    }
+}
+
+impl Disc {
+    pub fn number(&self) -> Option<u32> {
+        self.number
+    }
+
+    pub fn subtitle(&self) -> Option<&str> {
+        self.subtitle.as_deref()
+    }
+
+    pub fn songs(&self) -> &[SongId] {
+        &self.songs
+    }
 }
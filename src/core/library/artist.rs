@@ -9,6 +9,8 @@ pub struct Artist {
     pub(super) name: Arc<str>,
     pub(super) albums: Vec<AlbumId>,
     pub(super) singles: Vec<SongId>,
+    /// Songs composed by the artist.
+    pub(super) compositions: Vec<SongId>,
 }
 
 impl Artist {
@@ -17,6 +19,7 @@ impl Artist {
             name,
             albums: vec![],
             singles: vec![],
+            compositions: vec![],
         }
     }
 
@@ -31,4 +34,16 @@ impl Artist {
     pub fn singles(&self) -> &[SongId] {
         &self.singles
     }
+
+    /// Gets the songs composed by the artist.
+    pub fn compositions(&self) -> &[SongId] {
+        &self.compositions
+    }
+
+    /// Checks whether the artist has no songs.
+    pub fn is_empty(&self) -> bool {
+        self.albums.is_empty()
+            && self.singles.is_empty()
+            && self.compositions.is_empty()
+    }
 }
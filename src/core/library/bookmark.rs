@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Named position in a song added by the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Name of the bookmark.
+    pub name: String,
+    /// Position of the bookmark relative to the start of the song.
+    pub time: Duration,
+}
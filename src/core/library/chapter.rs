@@ -0,0 +1,189 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use symphonia::core::{
+    meta::{ChapterGroup, ChapterGroupItem, StandardTag},
+    units::Time,
+};
+
+use crate::core::Result;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Chapter of a song read from the metadata of the song (e.g. in audiobooks
+/// or DJ mixes).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chapter {
+    /// Start of the chapter relative to the start of the song.
+    pub start: Duration,
+    /// Title of the chapter.
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl Chapter {
+    /// Gets the chapters from the chapter group read by symphonia. Nested
+    /// groups are flattened. The chapters are ordered by their start.
+    pub fn from_symph(group: &ChapterGroup) -> Vec<Self> {
+        let mut res = vec![];
+        flatten_group(group, &mut res);
+        res.sort_by_key(|c| c.start);
+        res
+    }
+
+    /// Reads the Nero chapters (`moov.udta.chpl`) of mp4 file (e.g. `m4b`
+    /// audiobook). Returns empty vector if the file is not mp4 or if it has
+    /// no chapters. The chapters are ordered by their start.
+    ///
+    /// # Errors
+    /// - Failed to read the file.
+    pub fn read_mp4(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let mut f = BufReader::new(File::open(path)?);
+
+        let Some((typ, _)) = read_box_header(&mut f)? else {
+            return Ok(vec![]);
+        };
+        if &typ != b"ftyp" {
+            return Ok(vec![]);
+        }
+        f.seek(SeekFrom::Start(0))?;
+
+        let mut end = u64::MAX;
+        for name in [b"moov", b"udta", b"chpl"] {
+            match find_box(&mut f, end, name)? {
+                Some(e) => end = e,
+                None => return Ok(vec![]),
+            }
+        }
+
+        let mut res = read_chpl(&mut f)?;
+        res.sort_by_key(|c| c.start);
+        Ok(res)
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+fn flatten_group(group: &ChapterGroup, res: &mut Vec<Chapter>) {
+    for item in &group.items {
+        match item {
+            ChapterGroupItem::Group(g) => flatten_group(g, res),
+            ChapterGroupItem::Chapter(c) => {
+                let title = c.tags.iter().find_map(|t| match &t.std {
+                    Some(
+                        StandardTag::ChapterTitle(s)
+                        | StandardTag::TrackTitle(s),
+                    ) => Some(s.to_string()),
+                    _ => None,
+                });
+                res.push(Chapter {
+                    start: time_to_duration(c.start_time),
+                    title,
+                });
+            }
+        }
+    }
+}
+
+fn time_to_duration(t: Time) -> Duration {
+    let (secs, nanos) = t.parts();
+    if secs < 0 {
+        Duration::ZERO
+    } else {
+        Duration::new(secs as u64, nanos)
+    }
+}
+
+/// Reads the content of the `chpl` box. Start of the chapters is in units of
+/// 100 ns.
+fn read_chpl(f: &mut impl Read) -> io::Result<Vec<Chapter>> {
+    let [version, _, _, _] = read_arr(f)?;
+    if version != 0 {
+        read_arr::<4>(f)?;
+    }
+    let [cnt] = read_arr(f)?;
+
+    let mut res = vec![];
+    for _ in 0..cnt {
+        let start = u64::from_be_bytes(read_arr(f)?);
+        let [len] = read_arr(f)?;
+        let mut title = vec![0; len as usize];
+        f.read_exact(&mut title)?;
+        let title = String::from_utf8_lossy(&title).trim().to_string();
+        res.push(Chapter {
+            start: Duration::from_nanos(start.saturating_mul(100)),
+            title: (!title.is_empty()).then_some(title),
+        });
+    }
+
+    Ok(res)
+}
+
+/// Finds box with the given name among the boxes that end at `end`. If the
+/// box is found, the reader is positioned at its content and the end of the
+/// box is returned.
+fn find_box(
+    f: &mut (impl Read + Seek),
+    end: u64,
+    name: &[u8; 4],
+) -> io::Result<Option<u64>> {
+    loop {
+        let pos = f.stream_position()?;
+        if pos >= end {
+            return Ok(None);
+        }
+        let Some((typ, size)) = read_box_header(f)? else {
+            return Ok(None);
+        };
+        let header_end = f.stream_position()?;
+        let box_end = match size {
+            Some(s) => pos.saturating_add(s),
+            None => end,
+        };
+        // Invalid size would make the loop stuck or read outside the parent.
+        if box_end < header_end || box_end > end {
+            return Ok(None);
+        }
+        if &typ == name {
+            return Ok(Some(box_end));
+        }
+        f.seek(SeekFrom::Start(box_end))?;
+    }
+}
+
+/// Reads the header of mp4 box. Returns the type and size of the box. Size
+/// is [`None`] if the box extends to the end of the file. Returns [`None`]
+/// at the end of the file.
+fn read_box_header(
+    f: &mut impl Read,
+) -> io::Result<Option<([u8; 4], Option<u64>)>> {
+    let size = match read_arr(f) {
+        Ok(s) => u32::from_be_bytes(s),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    let typ = read_arr(f)?;
+    let size = match size {
+        0 => None,
+        1 => Some(u64::from_be_bytes(read_arr(f)?)),
+        s => Some(s.into()),
+    };
+    Ok(Some((typ, size)))
+}
+
+fn read_arr<const N: usize>(f: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut res = [0; N];
+    f.read_exact(&mut res)?;
+    Ok(res)
+}
@@ -0,0 +1,215 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::core::{Error, Result};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Cue sheet that splits audio files into tracks.
+#[derive(Debug, Clone, Default)]
+pub struct CueSheet {
+    /// Title of the album.
+    pub title: Option<String>,
+    /// Performer of the album.
+    pub performer: Option<String>,
+    /// Genre of the album (`REM GENRE`).
+    pub genre: Option<String>,
+    /// Year of release (`REM DATE`).
+    pub year: Option<i32>,
+    /// Disc number (`REM DISCNUMBER`).
+    pub disc: Option<u32>,
+    /// Total number of discs (`REM TOTALDISCS`).
+    pub disc_count: Option<u32>,
+    /// Audio files and their tracks.
+    pub files: Vec<CueFile>,
+}
+
+/// Audio file in cue sheet.
+#[derive(Debug, Clone)]
+pub struct CueFile {
+    /// Path to the audio file.
+    pub path: PathBuf,
+    /// Tracks in the file ordered by their start.
+    pub tracks: Vec<CueTrack>,
+}
+
+/// Track in cue sheet.
+#[derive(Debug, Clone, Default)]
+pub struct CueTrack {
+    /// Number of the track.
+    pub number: u32,
+    /// Title of the track.
+    pub title: Option<String>,
+    /// Performer of the track.
+    pub performer: Option<String>,
+    /// Songwriter of the track.
+    pub songwriter: Option<String>,
+    /// Start of the track in the file (`INDEX 01`).
+    pub start: Duration,
+}
+
+impl CueSheet {
+    /// Reads the cue sheet at the given path.
+    ///
+    /// # Errors
+    /// - Failed to read the file.
+    /// - The cue sheet has no tracks.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path)?;
+        let s = match String::from_utf8(data) {
+            Ok(s) => s,
+            // Not UTF-8, so this is probably Latin-1
+            Err(e) => e.as_bytes().iter().map(|b| *b as char).collect(),
+        };
+
+        let res = Self::parse(&s, path.parent().unwrap_or(Path::new("")));
+        if res.files.is_empty() {
+            return Error::invalid_value()
+                .msg(format!(
+                    "Cue sheet `{}` has no audio tracks.",
+                    path.display()
+                ))
+                .err();
+        }
+        Ok(res)
+    }
+
+    /// Parses the cue sheet. Relative paths are relative to `dir`. Unknown
+    /// commands and tracks that are not audio are ignored.
+    pub fn parse(s: &str, dir: &Path) -> Self {
+        let mut res = Self::default();
+        // Track that is being parsed. It is [`None`] before the first track
+        // and in tracks that are not audio.
+        let mut track: Option<CueTrack> = None;
+        let mut in_track = false;
+
+        for line in s.trim_start_matches('\u{feff}').lines() {
+            let (cmd, args) = split_arg(line.trim());
+            let (arg, rest) = split_arg(args);
+            match cmd.to_ascii_uppercase().as_str() {
+                "FILE" => {
+                    res.push_track(track.take());
+                    in_track = false;
+                    let path = dir.join(arg);
+                    res.files.push(CueFile {
+                        path: path.canonicalize().unwrap_or(path),
+                        tracks: vec![],
+                    });
+                }
+                "TRACK" => {
+                    res.push_track(track.take());
+                    in_track = true;
+                    let (typ, _) = split_arg(rest);
+                    if typ.eq_ignore_ascii_case("AUDIO") {
+                        track = arg.parse().ok().map(|number| CueTrack {
+                            number,
+                            ..Default::default()
+                        });
+                    }
+                }
+                "INDEX" => {
+                    let (time, _) = split_arg(rest);
+                    if let Some(t) = &mut track
+                        && arg.parse() == Ok(1)
+                        && let Some(start) = parse_time(time)
+                    {
+                        t.start = start;
+                    }
+                }
+                "TITLE" | "PERFORMER" | "SONGWRITER" if in_track => {
+                    if let Some(t) = &mut track
+                        && !arg.is_empty()
+                    {
+                        let field = match cmd.to_ascii_uppercase().as_str() {
+                            "TITLE" => &mut t.title,
+                            "PERFORMER" => &mut t.performer,
+                            _ => &mut t.songwriter,
+                        };
+                        *field = Some(arg.to_string());
+                    }
+                }
+                "TITLE" if !arg.is_empty() => {
+                    res.title = Some(arg.to_string())
+                }
+                "PERFORMER" if !arg.is_empty() => {
+                    res.performer = Some(arg.to_string())
+                }
+                "REM" if !in_track => res.read_rem(arg, rest),
+                _ => {}
+            }
+        }
+
+        res.push_track(track);
+        for f in &mut res.files {
+            f.tracks.sort_by_key(|t| t.start);
+        }
+        res.files.retain(|f| !f.tracks.is_empty());
+        res
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+impl CueSheet {
+    fn push_track(&mut self, track: Option<CueTrack>) {
+        if let Some(t) = track
+            && let Some(f) = self.files.last_mut()
+        {
+            f.tracks.push(t);
+        }
+    }
+
+    fn read_rem(&mut self, key: &str, val: &str) {
+        let (val, _) = split_arg(val);
+        if val.is_empty() {
+            return;
+        }
+
+        match key.to_ascii_uppercase().as_str() {
+            "GENRE" => self.genre = Some(val.to_string()),
+            "DATE" => {
+                // Date may also contain month and day.
+                self.year = val.get(..4).and_then(|y| y.parse().ok())
+            }
+            "DISCNUMBER" => self.disc = val.parse().ok(),
+            "TOTALDISCS" => self.disc_count = val.parse().ok(),
+            _ => {}
+        }
+    }
+}
+
+/// Splits the first argument from the rest of the line. The argument may be
+/// in quotes.
+fn split_arg(s: &str) -> (&str, &str) {
+    if let Some(s) = s.strip_prefix('"') {
+        let (arg, rest) = s.split_once('"').unwrap_or((s, ""));
+        (arg, rest.trim_start())
+    } else {
+        let (arg, rest) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+        (arg, rest.trim_start())
+    }
+}
+
+/// Parses time in the form `mm:ss:ff` where `ff` are frames (1/75 s).
+fn parse_time(s: &str) -> Option<Duration> {
+    let mut parts = s.split(':').map(|p| p.parse::<u64>().ok());
+    let (Some(Some(m)), Some(Some(sec)), Some(Some(f)), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Some(
+        Duration::from_secs(m * 60 + sec) + Duration::from_secs(f) / CD_FRAMES,
+    )
+}
+
+/// Number of CD frames in one second.
+const CD_FRAMES: u32 = 75;
@@ -192,7 +192,12 @@ impl ImageLookup<'_> {
         );
         let s = block_on(rt.request(move |app, _| {
             query
-                .get_ids(&app.library, true, Base::All, &app.player)
+                .get_ids(
+                    &app.library,
+                    app.config.sort_opts(),
+                    Base::All,
+                    &app.player,
+                )
                 .unwrap()
                 .into_iter()
                 .map(|i| app.library[i].path().to_owned())
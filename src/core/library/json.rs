@@ -7,8 +7,10 @@ use std::{
 use log::info;
 
 use crate::core::{
-    AppCtrl, Error, Job, JobMsg, Jobs, LogResult, Msg, Result, config::Config,
-    library::add_new_songs::construct_album_artists, player::Player,
+    AppCtrl, Error, Job, JobMsg, Jobs, LogResult, Msg, Result,
+    config::Config,
+    library::{SearchIndex, add_new_songs::construct_album_artists},
+    player::Player,
 };
 
 use super::{Library, Song, SongId};
@@ -38,6 +40,7 @@ impl Library {
                         construct_album_artists(&mut l.songs);
                     l.albums = albums.into();
                     l.artists = artists.into();
+                    l.search = SearchIndex::new(&l.songs).into();
                     l
                 })
                 .unwrap_or_default()
@@ -1,18 +1,27 @@
-use std::{collections::HashSet, fmt::Debug};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::Debug,
+    path::PathBuf,
+};
+
+use itertools::Itertools;
 
 use crate::{
     core::{
         AppCtrl, Error, Job, JobMsg, Msg, Result, UampApp,
+        config::Config,
         library::{
-            Albums, Artists, LoadOpts, add_new_songs::add_new_songs,
+            Albums, Artists, Library, LoadOpts, SearchIndex, SongEdit, Tag,
+            add_new_songs::{add_new_songs, apply_file_changes},
             tags::Tags,
         },
         player::AddPolicy,
+        server::{SubMsg, sub::LoadProgress},
     },
     ext::Alc,
 };
 
-use super::{LibraryUpdate, Song, SongId};
+use super::{LibraryUpdate, MetaChange, Song, SongId};
 
 //===========================================================================//
 //                                   Public                                  //
@@ -22,11 +31,15 @@ use super::{LibraryUpdate, Song, SongId};
 pub struct LibraryLoadResult {
     /// True if any songs were removed from the library.
     pub(super) removed: bool,
+    /// True if any songs were reloaded or their file stamps were filled.
+    pub(super) modified: bool,
     /// The new library contents (all songs not only the new ones)
     pub(super) songs: Vec<Song>,
     pub(super) albums: Albums,
     pub(super) artists: Artists,
     pub(super) tags: Tags,
+    /// Search index of the new library contents.
+    pub(super) search: SearchIndex,
     /// Determines what to do with the new songs.
     pub(super) add_policy: Option<AddPolicy>,
     /// Index of first new song.
@@ -35,10 +48,21 @@ pub struct LibraryLoadResult {
     pub(super) sparse_new: Vec<SongId>,
 }
 
+/// Change of songs made while library load is running. It is applied again
+/// to the loaded songs when the load finishes.
+#[derive(Debug)]
+pub(super) enum LoadChange {
+    /// The songs were edited.
+    Edit(Vec<SongId>, SongEdit),
+    /// The songs were removed from the library.
+    Remove(Vec<SongId>),
+}
+
 impl LibraryLoadResult {
     /// Checks if there is any change in the library.
     pub fn any_change(&self) -> bool {
         self.removed
+            || self.modified
             || self.first_new != self.songs.len()
             || !self.sparse_new.is_empty()
     }
@@ -49,6 +73,7 @@ impl Debug for LibraryLoadResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LibraryLoadResult")
             .field("removed", &self.removed)
+            .field("modified", &self.modified)
             .field("songs.len", &self.songs.len())
             .field("albums.len", &self.albums.len())
             .field("artists.len", &self.artists.len())
@@ -67,53 +92,36 @@ impl UampApp {
         ctrl: &mut AppCtrl,
         opts: LoadOpts,
     ) -> Result<()> {
-        if self.jobs.is_running(Job::LIBRARY_LOAD) {
-            return Error::invalid_operation()
-                .msg("Cannot load library.")
-                .reason("Library load is already in progress.")
-                .err();
-        }
-
-        let conf = self.config.clone();
-        let songs = self.library.clone_songs();
-        let albums = self.library.clone_albums();
-        let artists = self.library.clone_artists();
-        let tags = self.library.clone_tags();
-        let remove_missing =
-            opts.remove_missing.unwrap_or(conf.remove_missing_on_load());
-
-        let task = move || {
-            let mut res = Box::new(LibraryLoadResult {
-                removed: false,
-                first_new: songs.len(),
-                add_policy: opts.add_to_playlist,
-                sparse_new: vec![],
-                // Do the cloning on the separate thread.
-                songs: Alc::take(songs),
-                albums: Alc::take(albums),
-                artists: Alc::take(artists),
-                tags: Alc::take(tags),
-            });
-
-            add_new_songs(&mut res, &conf, remove_missing);
-
-            if res.any_change() {
-                Msg::Job(JobMsg::LibraryLoad(Ok(Some(res))))
-            } else {
-                Msg::Job(JobMsg::LibraryLoad(Ok(None)))
-            }
+        let remove_missing = opts
+            .remove_missing
+            .unwrap_or(self.config.remove_missing_on_load());
+        let rt = self.rt.andle();
+        let progress = move |done, total| {
+            rt.msg(Msg::fn_delegate(move |app, _| {
+                app.client_update(SubMsg::LibraryLoadProgress(
+                    LoadProgress::new(done, total),
+                ));
+                Ok(vec![])
+            }))
         };
+        self.start_library_load(
+            ctrl,
+            opts.add_to_playlist,
+            move |res, conf| {
+                add_new_songs(res, conf, remove_missing, &progress)
+            },
+        )
+    }
 
-        self.jobs.run(Job::LIBRARY_LOAD);
-
-        ctrl.task(async move {
-            match tokio::task::spawn_blocking(task).await {
-                Ok(r) => r,
-                Err(e) => Msg::Job(JobMsg::LibraryLoad(Err(e.into()))),
-            }
-        });
-
-        Ok(())
+    /// Applies changes of the given files to the library on another thread.
+    pub fn start_apply_file_changes(
+        &mut self,
+        ctrl: &mut AppCtrl,
+        changes: BTreeSet<PathBuf>,
+    ) -> Result<()> {
+        self.start_library_load(ctrl, None, move |res, conf| {
+            apply_file_changes(res, conf, changes)
+        })
     }
 
     /// Finishes loading songs started with `start_get_new_songs`.
@@ -126,17 +134,28 @@ impl UampApp {
         res: Option<Box<LibraryLoadResult>>,
     ) -> Result<()> {
         self.jobs.finish(Job::LIBRARY_LOAD);
+        let mut changes = self.library.load_changes.take().unwrap_or_default();
 
-        let Some(res) = res else {
+        let Some(mut res) = res else {
             return Ok(());
         };
 
+        // The library may have changed while it was loading.
+        res.inherit_user_data(&self.library);
+        for c in &mut changes {
+            let (LoadChange::Edit(ids, _) | LoadChange::Remove(ids)) = c;
+            ids.retain(|s| {
+                self.library.is_tmp(*s) || res.is_same_song(*s, &self.library)
+            });
+        }
+
         let old_cnt = self.library.songs().len();
 
         *self.library.mut_songs() = res.songs.into();
         self.library.albums = res.albums.into();
         self.library.artists = res.artists.into();
         self.library.tags = res.tags.into();
+        self.library.search = res.search.into();
         if res.removed {
             if res.first_new < old_cnt || !res.sparse_new.is_empty() {
                 // New songs ids replaced old song ids.
@@ -150,8 +169,12 @@ impl UampApp {
             } else {
                 self.library.update(LibraryUpdate::RemoveData);
             }
-        } else {
+        } else if res.first_new != self.library.songs().len()
+            || !res.sparse_new.is_empty()
+        {
             self.library.update(LibraryUpdate::NewData);
+        } else {
+            self.library.update_meta(MetaChange::all());
         }
 
         self.player.add_songs(
@@ -163,6 +186,15 @@ impl UampApp {
             res.add_policy,
         );
 
+        for c in changes {
+            match c {
+                LoadChange::Edit(ids, edit) => {
+                    self.library.edit_songs(&ids, &edit)
+                }
+                LoadChange::Remove(ids) => self.library.remove_songs(ids),
+            }
+        }
+
         self.client_update_set_all();
 
         match self.library.start_to_default_json(
@@ -179,3 +211,118 @@ impl UampApp {
         }
     }
 }
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+impl UampApp {
+    /// Runs `f` on the library data on another thread.
+    fn start_library_load(
+        &mut self,
+        ctrl: &mut AppCtrl,
+        add_policy: Option<AddPolicy>,
+        f: impl FnOnce(&mut LibraryLoadResult, &Config) + Send + 'static,
+    ) -> Result<()> {
+        if self.jobs.is_running(Job::LIBRARY_LOAD) {
+            return Error::invalid_operation()
+                .msg("Cannot load library.")
+                .reason("Library load is already in progress.")
+                .err();
+        }
+
+        self.library.load_changes = Some(vec![]);
+        let conf = self.config.clone();
+        let songs = self.library.clone_songs();
+        let albums = self.library.clone_albums();
+        let artists = self.library.clone_artists();
+        let tags = self.library.clone_tags();
+
+        let task = move || {
+            let mut res = Box::new(LibraryLoadResult {
+                removed: false,
+                modified: false,
+                first_new: songs.len(),
+                add_policy,
+                sparse_new: vec![],
+                // Do the cloning on the separate thread.
+                songs: Alc::take(songs),
+                albums: Alc::take(albums),
+                artists: Alc::take(artists),
+                tags: Alc::take(tags),
+                search: SearchIndex::default(),
+            });
+
+            f(&mut res, &conf);
+
+            if res.any_change() {
+                res.search = SearchIndex::new(&res.songs);
+                Msg::Job(JobMsg::LibraryLoad(Ok(Some(res))))
+            } else {
+                Msg::Job(JobMsg::LibraryLoad(Ok(None)))
+            }
+        };
+
+        self.jobs.run(Job::LIBRARY_LOAD);
+
+        ctrl.task(async move {
+            match tokio::task::spawn_blocking(task).await {
+                Ok(r) => r,
+                Err(e) => Msg::Job(JobMsg::LibraryLoad(Err(e.into()))),
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl LibraryLoadResult {
+    /// Takes the data that is not stored in the files (statistics, rating,
+    /// tags, ...) from the songs in the current library. It may have changed
+    /// while the library was loading.
+    fn inherit_user_data(&mut self, lib: &Library) {
+        for (i, s) in lib.songs().iter().enumerate() {
+            if self.is_same_song(SongId::norm(i), lib) {
+                self.songs[i].inherit(s);
+            }
+        }
+
+        for (name, tag) in &lib.tags().0 {
+            self.tags
+                .0
+                .entry(name.clone())
+                .or_insert_with(|| Tag::new(name.clone(), tag.hidden))
+                .hidden = tag.hidden;
+        }
+
+        // Songs were tagged according to the current library.
+        for tag in self.tags.0.values_mut() {
+            let cur = lib.get_tag_songs(&tag.name);
+            tag.songs = cur
+                .iter()
+                .chain(&tag.songs)
+                .copied()
+                .filter(|s| {
+                    lib.is_tmp(*s)
+                        || self.songs.get(s.as_norm()).is_some_and(|s| {
+                            !s.is_deleted() && s.tags.contains(&tag.name)
+                        })
+                })
+                .unique()
+                .collect();
+        }
+    }
+
+    /// Checks whether the song with the given id is the same song in the
+    /// loaded songs and in the current library.
+    fn is_same_song(&self, id: SongId, lib: &Library) -> bool {
+        let (Some(new), Some(cur)) =
+            (self.songs.get(id.as_norm()), lib.songs().get(id.as_norm()))
+        else {
+            return false;
+        };
+        !new.is_deleted()
+            && new.path() == cur.path()
+            && new.range().map(|r| r.track) == cur.range().map(|r| r.track)
+    }
+}
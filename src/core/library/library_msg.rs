@@ -1,8 +1,8 @@
-use std::mem;
+use std::{mem, path::PathBuf};
 
-use crate::core::{Job, Result, UampApp};
+use crate::core::{AppCtrl, Error, Job, LogResult, Msg, Result, UampApp};
 
-use super::{LibraryUpdate, SongId};
+use super::{LibraryUpdate, MetaChange, SongEdit, SongId};
 
 impl UampApp {
     /// Finish up task for saving songs to json started with
@@ -26,8 +26,98 @@ impl UampApp {
     }
 
     /// Updates references to songs in the scope of the library with the given
-    /// change and returns the change.
-    pub(in crate::core) fn library_routine(&mut self) -> LibraryUpdate {
-        mem::replace(&mut self.library.lib_update, LibraryUpdate::None)
+    /// change and returns the change together with the changed metadata.
+    pub(in crate::core) fn library_routine(
+        &mut self,
+    ) -> (LibraryUpdate, MetaChange) {
+        let up =
+            mem::replace(&mut self.library.lib_update, LibraryUpdate::None);
+        let meta = mem::take(&mut self.library.meta_change);
+
+        // ReplaceData is handled separately in `id_replace`
+        if up >= LibraryUpdate::RemoveData && up != LibraryUpdate::ReplaceData
+        {
+            self.library
+                .retain_playlist_songs(|s, l| !l[s].is_deleted());
+        }
+
+        (up, meta)
+    }
+
+    /// Writes the metadata changes to the files of the given songs. The
+    /// files are written in background.
+    ///
+    /// # Errors
+    /// - Some of the songs are part of cue sheet.
+    pub(in crate::core) fn write_tags(
+        &mut self,
+        ctrl: &mut AppCtrl,
+        songs: &[SongId],
+        edit: SongEdit,
+    ) -> Result<()> {
+        let mut errs = vec![];
+        let mut files = vec![];
+        for s in songs {
+            let song = &self.library[s];
+            // Tags of the audio file are shared by all the tracks.
+            if song.range().is_some() {
+                errs.push(
+                    Error::invalid_operation()
+                        .msg(format!(
+                            "Cannot write tags to `{}`.",
+                            song.path().display()
+                        ))
+                        .reason("The song is part of cue sheet."),
+                );
+            } else {
+                files.push((*s, song.path().to_owned()));
+            }
+        }
+
+        if !files.is_empty() {
+            let task = move || {
+                let errs = files
+                    .iter()
+                    .filter_map(|(_, p)| {
+                        edit.write_to(p)
+                            .map_err(|e| {
+                                e.prepend(format!(
+                                    "Failed to write tags to `{}`.",
+                                    p.display()
+                                ))
+                            })
+                            .err()
+                    })
+                    .collect();
+                (files, errs)
+            };
+            ctrl.task(async move {
+                match tokio::task::spawn_blocking(task).await {
+                    Ok((files, errs)) => Msg::fn_delegate(move |app, _| {
+                        app.finish_write_tags(&files);
+                        Error::multiple(errs)?;
+                        Ok(vec![])
+                    }),
+                    Err(e) => {
+                        Err::<(), _>(e).or_log_err("Failed to write tags.");
+                        Msg::None
+                    }
+                }
+            });
+        }
+
+        Error::multiple(errs)
+    }
+
+    /// Updates the file stamps of the songs whose tags were written so that
+    /// they are not considered changed.
+    fn finish_write_tags(&mut self, files: &[(SongId, PathBuf)]) {
+        for (id, path) in files {
+            let song = &mut self.library[id];
+            // The song may have been removed or replaced in the meantime.
+            if !song.is_deleted() && song.path() == path {
+                song.update_file_stamp();
+            }
+        }
     }
 }
@@ -1,23 +1,30 @@
 use itertools::Itertools;
+use log::warn;
 use serde::{Deserialize, Serialize};
 use uamp_proc::TrackChange;
 
 use std::{
     cell::Cell,
     collections::{BTreeSet, HashMap, HashSet},
+    mem,
     ops::{Index, IndexMut},
     path::Path,
+    sync::Arc,
 };
 
 use crate::{
     core::{
-        Result,
+        Error, LogResult, Result,
         library::{Album, AlbumId, Artist, ArtistId, Tag, TagId, tags::Tags},
     },
     ext::Alc,
 };
 
-use super::{LibraryUpdate, Song, SongId};
+use super::{
+    Bookmark, CueSheet, LibraryUpdate, LoadChange, MetaChange, PlaylistEntry,
+    SearchIndex, Song, SongEdit, SongId,
+    add_new_songs::{album_artist_ids, is_cue_file, update_album_artists},
+};
 
 //===========================================================================//
 //                                   Public                                  //
@@ -25,6 +32,8 @@ use super::{LibraryUpdate, Song, SongId};
 
 pub type Albums = HashMap<AlbumId, Album>;
 pub type Artists = HashMap<ArtistId, Artist>;
+/// Key is the name of the playlist.
+pub type Playlists = HashMap<Arc<str>, Vec<SongId>>;
 
 #[derive(Serialize, Deserialize, Debug, TrackChange)]
 pub struct Library {
@@ -51,12 +60,28 @@ pub struct Library {
     #[track_ref(pub, pub)]
     pub(super) tags: Alc<Tags>,
 
+    /// Named playlists saved by the user.
+    #[serde(default)]
+    #[track_ref(pub, pub)]
+    pub(super) playlists: Alc<Playlists>,
+
+    /// Index for fast searching in songs.
+    #[serde(skip, default)]
+    pub(super) search: Alc<SearchIndex>,
+
     // Other fields
     /// invalid song
     #[serde(skip, default = "default_ghost")]
     ghost: Song,
     #[serde(skip)]
     pub(super) lib_update: LibraryUpdate,
+    /// Metadata that changed with [`LibraryUpdate::Metadata`].
+    #[serde(skip)]
+    pub(super) meta_change: MetaChange,
+    /// Changes made while library load is running. [`None`] if no library
+    /// load is running.
+    #[serde(skip)]
+    pub(super) load_changes: Option<Vec<LoadChange>>,
 
     // attributes for the auto field
     #[serde(skip)]
@@ -77,7 +102,11 @@ impl Library {
             albums: Alc::default(),
             artists: Alc::default(),
             tags: Alc::default(),
+            playlists: Alc::default(),
+            search: Alc::default(),
             lib_update: LibraryUpdate::None,
+            meta_change: MetaChange::NONE,
+            load_changes: None,
             change: Cell::new(true),
             ghost: Song::invalid(),
         }
@@ -108,6 +137,15 @@ impl Library {
         Alc::clone(&mut self.tags)
     }
 
+    pub fn clone_playlists(&mut self) -> Alc<Playlists> {
+        Alc::clone(&mut self.playlists)
+    }
+
+    /// Gets the index for fast searching in songs.
+    pub fn search_index(&self) -> &SearchIndex {
+        &self.search
+    }
+
     /// Change the library update state. Call this when you change some data in
     /// the library - it will eventually propagate the change.
     pub fn update(&mut self, up: LibraryUpdate) {
@@ -116,6 +154,13 @@ impl Library {
         }
     }
 
+    /// Change the library update state to [`LibraryUpdate::Metadata`] and
+    /// mark which metadata has changed.
+    pub fn update_meta(&mut self, change: MetaChange) {
+        self.update(LibraryUpdate::Metadata);
+        self.meta_change |= change;
+    }
+
     /*/// Filters songs in the library
     pub fn filter(&self, filter: &Filter) -> AlcVec<SongId> {
         let mut buf = String::new();
@@ -161,7 +206,11 @@ impl Library {
             albums: Alc::clone(&mut self.albums),
             artists: Alc::clone(&mut self.artists),
             tags: Alc::clone(&mut self.tags),
+            playlists: Alc::clone(&mut self.playlists),
+            search: Alc::clone(&mut self.search),
             lib_update: LibraryUpdate::None,
+            meta_change: MetaChange::NONE,
+            load_changes: None,
             ghost: self.ghost.clone(),
             change: self.change.clone(),
         }
@@ -181,27 +230,107 @@ impl Library {
         SongId::tmp(self.tmp_songs.len() - 1)
     }
 
-    /// Add temporary song that will be automatically removed when it is
-    /// removed from playlist.
+    /// Add temporary songs that will be automatically removed when they are
+    /// removed from playlist. Cue sheets add all their tracks.
     ///
     /// # Errors
     /// - The song fails to load from the given path.
-    pub fn add_tmp_path(&mut self, path: impl AsRef<Path>) -> Result<SongId> {
-        Ok(self.add_tmp_song(
-            Song::from_path(path.as_ref(), BTreeSet::new()).map_err(|e| {
-                e.prepend(format!(
-                    "Failed to add tmp song `{}`",
-                    path.as_ref().display()
-                ))
-            })?,
-        ))
+    pub fn add_tmp_path(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<SongId>> {
+        Ok(load_tmp_songs(path.as_ref())?
+            .into_iter()
+            .map(|s| self.add_tmp_song(s))
+            .collect())
     }
 
     pub fn add_tmp_paths(
         &mut self,
         paths: &[impl AsRef<Path>],
     ) -> Result<Vec<SongId>> {
-        paths.iter().map(|a| self.add_tmp_path(a)).try_collect()
+        let mut res = vec![];
+        for p in paths {
+            res.extend(self.add_tmp_path(p)?);
+        }
+        Ok(res)
+    }
+
+    /// Gets ids of songs of the given playlist entries. Entries that are not
+    /// in the library (or whose songs are deleted) are added as temporary
+    /// songs. Entries with cue sheet without track refer to all the tracks.
+    /// Songs that fail to load are skipped.
+    pub fn get_or_add_tmp_paths(
+        &mut self,
+        entries: &[PlaylistEntry],
+    ) -> Vec<SongId> {
+        let ids: Vec<Vec<_>> = {
+            // Tracks of cue sheets are identified by the cue sheet and the
+            // track number, not by the shared audio file.
+            let mut lib: HashMap<_, Vec<_>> = HashMap::new();
+            for s in self.iter() {
+                let song = &self[s];
+                match song.range() {
+                    Some(r) => {
+                        lib.entry((r.sheet.as_path(), Some(r.track)))
+                            .or_default()
+                            .push(s);
+                        lib.entry((r.sheet.as_path(), None))
+                            .or_default()
+                            .push(s);
+                    }
+                    None => {
+                        lib.entry((song.path(), None)).or_default().push(s)
+                    }
+                }
+            }
+            entries
+                .iter()
+                .map(|e| {
+                    lib.get(&(e.path.as_path(), e.track))
+                        .cloned()
+                        .unwrap_or_default()
+                })
+                .collect()
+        };
+
+        // Each file is loaded only once.
+        let mut loaded: HashMap<&Path, Vec<Song>> = HashMap::new();
+        let mut res = vec![];
+        for (e, ids) in entries.iter().zip(ids) {
+            if !ids.is_empty() {
+                res.extend(ids);
+                continue;
+            }
+
+            let songs = loaded.entry(&e.path).or_insert_with(|| {
+                load_tmp_songs(&e.path)
+                    .or_warn("Skipping playlist entry.")
+                    .unwrap_or_default()
+            });
+            let songs = songs
+                .iter()
+                .filter(|s| {
+                    e.track.is_none_or(|t| {
+                        s.range().is_some_and(|r| r.track == t)
+                    })
+                })
+                .cloned()
+                .collect_vec();
+            if songs.is_empty()
+                && let Some(t) = e.track
+            {
+                warn!(
+                    "Skipping playlist entry: `{}` has no track {t}.",
+                    e.path.display()
+                );
+            }
+            for s in songs {
+                res.push(self.add_tmp_song(s));
+            }
+        }
+
+        res
     }
 
     /// Checks if song is temporary or not
@@ -211,12 +340,99 @@ impl Library {
     }
 
     pub fn remove_songs(&mut self, s: impl IntoIterator<Item = SongId>) {
-        for s in s {
-            self.remove_song_inner(s);
+        let s: Vec<_> = s.into_iter().collect();
+        for s in &s {
+            self.remove_song_inner(*s);
+        }
+        if let Some(c) = &mut self.load_changes {
+            c.push(LoadChange::Remove(s));
         }
         self.update(LibraryUpdate::RemoveData);
     }
 
+    /// Changes the metadata of the given songs.
+    pub fn edit_songs(&mut self, songs: &[SongId], edit: &SongEdit) {
+        if let Some(c) = &mut self.load_changes {
+            c.push(LoadChange::Edit(songs.to_vec(), edit.clone()));
+        }
+        let ids = songs
+            .iter()
+            .copied()
+            .filter(|s| !self.is_tmp(*s))
+            .collect_vec();
+        let mut albums = BTreeSet::new();
+        let mut artists = BTreeSet::new();
+        for s in &ids {
+            album_artist_ids(
+                &self.songs[s.as_norm()],
+                &mut albums,
+                &mut artists,
+            );
+        }
+
+        for s in songs {
+            self[s].apply_edit(edit);
+            if !self.is_tmp(*s) {
+                self.search.update_song(*s, &self.songs[s.as_norm()]);
+            }
+        }
+
+        update_album_artists(
+            &mut self.songs,
+            &ids,
+            &mut self.albums,
+            &mut self.artists,
+            albums,
+            artists,
+        );
+        self.update_meta(MetaChange::INFO);
+    }
+
+    /// Sets the rating of the given songs. [`None`] removes the rating.
+    pub fn rate_songs(&mut self, songs: &[SongId], rating: Option<u8>) {
+        for s in songs {
+            self[s].set_rating(rating);
+        }
+        self.update_meta(MetaChange::RATING);
+    }
+
+    /// Adds the bookmark to the given song.
+    pub fn add_bookmark(&mut self, song: SongId, bookmark: Bookmark) {
+        self[song].add_bookmark(bookmark);
+        self.update_meta(MetaChange::PLAYBACK);
+    }
+
+    /// Removes bookmarks with the given name from the given song.
+    ///
+    /// # Errors
+    /// - The song has no bookmark with the given name.
+    pub fn remove_bookmark(&mut self, song: SongId, name: &str) -> Result<()> {
+        if !self[song].remove_bookmark(name) {
+            return Error::invalid_operation()
+                .msg(format!("Cannot remove bookmark `{name}`."))
+                .reason("The song has no bookmark with this name.")
+                .err();
+        }
+        self.update_meta(MetaChange::PLAYBACK);
+        Ok(())
+    }
+
+    /// Removes the resume positions of the given songs. Returns the songs
+    /// that had resume position.
+    pub fn clear_resume(
+        &mut self,
+        songs: impl IntoIterator<Item = SongId>,
+    ) -> Vec<SongId> {
+        let res: Vec<_> = songs
+            .into_iter()
+            .filter(|s| self[s].resume_pos.take().is_some())
+            .collect();
+        if !res.is_empty() {
+            self.update_meta(MetaChange::PLAYBACK);
+        }
+        res
+    }
+
     fn remove_song_inner(&mut self, s: SongId) {
         let s = &mut self[s];
         if !s.is_deleted() {
@@ -255,6 +471,7 @@ impl Library {
 
         tag.songs.retain(|s| !rem.contains(s));
         tag.songs.extend_from_slice(songs);
+        self.update_meta(MetaChange::TAGS);
     }
 
     pub fn remove_tag<S: AsRef<SongId>>(
@@ -267,6 +484,9 @@ impl Library {
             .map(|a| *a.as_ref())
             .filter(|a| self[a].tags.remove(name))
             .collect();
+        if !rem.is_empty() {
+            self.update_meta(MetaChange::TAGS);
+        }
         let Some(tag) = self.mut_tags().0.get_mut(name) else {
             return;
         };
@@ -282,6 +502,90 @@ impl Library {
             .map(|a| a.songs.as_slice())
             .unwrap_or_default()
     }
+
+    pub fn get_playlist(&self, name: &str) -> Option<&[SongId]> {
+        self.playlists().get(name).map(|a| a.as_slice())
+    }
+
+    /// Creates new empty named playlist.
+    ///
+    /// # Errors
+    /// - Playlist with the given name already exists.
+    pub fn create_playlist(&mut self, name: Arc<str>) -> Result<()> {
+        if self.playlists().contains_key(&name) {
+            return Error::invalid_operation()
+                .msg(format!("Playlist `{name}` already exists."))
+                .err();
+        }
+        self.mut_playlists().insert(name, vec![]);
+        Ok(())
+    }
+
+    /// Deletes the named playlist.
+    ///
+    /// # Errors
+    /// - There is no playlist with the given name.
+    pub fn delete_playlist(&mut self, name: &str) -> Result<()> {
+        if self.mut_playlists().remove(name).is_none() {
+            return no_playlist_err(name);
+        }
+        Ok(())
+    }
+
+    /// Appends the songs to the end of the named playlist. Temporary songs
+    /// are ignored. Returns the new songs in the playlist.
+    ///
+    /// # Errors
+    /// - There is no playlist with the given name.
+    pub fn append_to_playlist(
+        &mut self,
+        name: &str,
+        songs: &[SongId],
+    ) -> Result<&[SongId]> {
+        let songs: Vec<_> =
+            songs.iter().copied().filter(|s| !self.is_tmp(*s)).collect();
+        let Some(pl) = self.mut_playlists().get_mut(name) else {
+            return no_playlist_err(name);
+        };
+        pl.extend(songs);
+        Ok(pl)
+    }
+
+    /// Removes all occurences of the songs from the named playlist. Returns
+    /// the new songs in the playlist.
+    ///
+    /// # Errors
+    /// - There is no playlist with the given name.
+    pub fn remove_from_playlist(
+        &mut self,
+        name: &str,
+        songs: &[SongId],
+    ) -> Result<&[SongId]> {
+        let rem: HashSet<_> = songs.iter().copied().collect();
+        let Some(pl) = self.mut_playlists().get_mut(name) else {
+            return no_playlist_err(name);
+        };
+        pl.retain(|s| !rem.contains(s));
+        Ok(pl)
+    }
+
+    /// Retain only songs in the named playlists that satisfy the predicate.
+    pub fn retain_playlist_songs(
+        &mut self,
+        f: impl Fn(SongId, &Library) -> bool,
+    ) {
+        let mut pls = mem::take(&mut self.playlists);
+        let mut changed = false;
+        for songs in pls.values_mut() {
+            let len = songs.len();
+            songs.retain(|s| f(*s, self));
+            changed |= len != songs.len();
+        }
+        self.playlists = pls;
+        if changed {
+            self.change();
+        }
+    }
 }
 
 impl Index<SongId> for Library {
@@ -345,6 +649,25 @@ impl Default for Library {
 //                                  Private                                  //
 //===========================================================================//
 
+/// Loads songs from the file. Cue sheets are loaded as all their tracks.
+fn load_tmp_songs(path: &Path) -> Result<Vec<Song>> {
+    let res = if is_cue_file(path) {
+        CueSheet::load(path)
+            .and_then(|c| Song::from_cue(path, &c, BTreeSet::new()))
+    } else {
+        Song::from_path(path, BTreeSet::new()).map(|s| vec![s])
+    };
+    res.map_err(|e| {
+        e.prepend(format!("Failed to add tmp song `{}`", path.display()))
+    })
+}
+
 fn default_ghost() -> Song {
     Song::invalid()
 }
+
+fn no_playlist_err<T>(name: &str) -> Result<T> {
+    Error::invalid_operation()
+        .msg(format!("There is no playlist with the name `{name}`."))
+        .err()
+}
@@ -1,3 +1,5 @@
+use bitflags::bitflags;
+
 //===========================================================================//
 //                                   Public                                  //
 //===========================================================================//
@@ -19,3 +21,26 @@ pub enum LibraryUpdate {
     /// [`crate::core::UampApp::id_replace`].
     ReplaceData = 4,
 }
+
+bitflags! {
+    /// Describes which metadata of songs has changed with
+    /// [`LibraryUpdate::Metadata`].
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct MetaChange: u32 {
+        const NONE = 0x0;
+        /// Information read from the tags of the audio files (title,
+        /// artists, album, ...).
+        const INFO = 0x1;
+        /// Length of songs.
+        const LENGTH = 0x2;
+        /// Rating of songs.
+        const RATING = 0x4;
+        /// Uamp tags of songs.
+        const TAGS = 0x8;
+        /// Play statistics of songs.
+        const STATS = 0x10;
+        /// Data related to playback (resume position, bookmarks, replay
+        /// gain).
+        const PLAYBACK = 0x20;
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    collections::BTreeSet,
+    mem,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use notify::{INotifyWatcher, RecursiveMode, Watcher};
+
+use crate::core::{
+    AppCtrl, Job, LogResult, Msg, Result, RtAndle, UampApp, config::Config,
+};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+impl UampApp {
+    /// Starts or stops watching the search paths for changes based on the
+    /// configuration.
+    pub(in crate::core) fn rewatch_library(&mut self) -> Result<()> {
+        self.library_watch = None;
+        if self.config.watch_library() {
+            self.library_watch =
+                Some(watch_library(self.rt.andle(), &self.config)?);
+        }
+        Ok(())
+    }
+
+    /// Applies the changes of the given files to the library. If library is
+    /// being loaded, the changes are applied after the load finishes.
+    pub(in crate::core) fn library_files_changed(
+        &mut self,
+        ctrl: &mut AppCtrl,
+        paths: BTreeSet<PathBuf>,
+    ) -> Result<()> {
+        self.library_changes.extend(paths);
+        if self.library_changes.is_empty()
+            || self.jobs.is_running(Job::LIBRARY_LOAD)
+        {
+            return Ok(());
+        }
+
+        let changes = mem::take(&mut self.library_changes);
+        self.start_apply_file_changes(ctrl, changes)
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+/// Changes are applied only after there is no change for this long.
+const DEBOUNCE_TIME: Duration = Duration::from_secs(2);
+
+fn watch_library(rt: RtAndle, conf: &Config) -> Result<INotifyWatcher> {
+    let (send, recv) = mpsc::channel();
+
+    let mut watcher =
+        notify::recommended_watcher(move |res: std::result::Result<_, _>| {
+            let Some(v): Option<notify::Event> =
+                res.or_log_err("Library watch failed")
+            else {
+                return;
+            };
+
+            // Songs are accessed when they are played.
+            if v.kind.is_access() {
+                return;
+            }
+
+            for path in v.paths {
+                _ = send.send(path);
+            }
+        })?;
+
+    let mode = if conf.recursive_search() {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+
+    for p in conf.search_paths() {
+        let Some(p) = p.canonicalize().or_log_err_with(|| {
+            format!("Cannot watch search path `{}`", p.display())
+        }) else {
+            continue;
+        };
+        watcher.watch(&p, mode).or_log_err_with(|| {
+            format!("Failed to watch search path `{}`", p.display())
+        });
+    }
+
+    thread::spawn(move || debounce(rt, recv));
+
+    Ok(watcher)
+}
+
+/// Collects the changed paths until there is no change for
+/// [`DEBOUNCE_TIME`]. Ends when the watcher is dropped.
+fn debounce(rt: RtAndle, recv: Receiver<PathBuf>) {
+    while let Ok(path) = recv.recv() {
+        let mut paths = BTreeSet::from([path]);
+        loop {
+            match recv.recv_timeout(DEBOUNCE_TIME) {
+                Ok(path) => _ = paths.insert(path),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        rt.msg(Msg::fn_delegate(move |app, ctrl| {
+            app.library_files_changed(ctrl, paths)?;
+            Ok(vec![])
+        }));
+    }
+}
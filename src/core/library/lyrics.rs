@@ -0,0 +1,236 @@
+use std::{fs, io::ErrorKind, path::Path, time::Duration};
+
+use lofty::id3::v2::{
+    FrameFlags, SyncTextContentType, SynchronizedTextFrame, TimestampFormat,
+};
+use serde::{Deserialize, Serialize};
+use symphonia::core::meta::{RawValue, StandardTag};
+
+use crate::{core::Result, ext::str_to_duration};
+
+use super::symph_tags::read_tags;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Lyrics of a song.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Lyrics {
+    /// `true` if the lines have timestamps.
+    pub synced: bool,
+    /// Lines of the lyrics. Lines of synced lyrics are ordered by time.
+    pub lines: Vec<LyricsLine>,
+}
+
+/// Single line of lyrics.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LyricsLine {
+    /// Time when the line starts. It is set only for synced lyrics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<Duration>,
+    /// Text of the line.
+    pub text: String,
+}
+
+impl Lyrics {
+    /// Loads lyrics of the song with the given path. Synced lyrics are
+    /// preferred. Sidecar `.lrc` file next to the song is preferred over
+    /// lyrics embedded in the song.
+    ///
+    /// # Errors
+    /// - Failed to read existing sidecar file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        let mut res = vec![];
+
+        match fs::read_to_string(path.with_extension("lrc")) {
+            Ok(s) => res.push(Self::from_lrc(&s)),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        // Files not supported by symphonia may be supported by plugins, so
+        // failure to read the tags is not error.
+        _ = read_tags(path, |t| {
+            if let Some(StandardTag::Lyrics(l)) = &t.std {
+                res.push(Self::from_lrc(l));
+            } else if t.raw.key == "SYLT"
+                && let RawValue::Binary(b) = &t.raw.value
+                && let Some(l) = Self::from_sylt(b)
+            {
+                res.push(l);
+            }
+        });
+
+        res.retain(|l| !l.lines.is_empty());
+        if res.is_empty() {
+            return Ok(None);
+        }
+        let idx = res.iter().position(|l| l.synced).unwrap_or_default();
+        Ok(Some(res.swap_remove(idx)))
+    }
+
+    /// Parses lyrics in the lrc format. If there are no timestamps, the
+    /// lyrics are unsynced and each line of the string is line of the lyrics.
+    pub fn from_lrc(s: &str) -> Self {
+        // Offset in milliseconds. Positive offset makes the lines appear
+        // sooner.
+        let mut offset = 0_i64;
+        let mut timed = vec![];
+        let mut plain = vec![];
+
+        for line in s.lines() {
+            let mut text = line.trim();
+            let mut times = vec![];
+            let mut is_tag = false;
+
+            while let Some((inner, rest)) =
+                text.strip_prefix('[').and_then(|t| t.split_once(']'))
+            {
+                if let Some(t) = parse_time(inner) {
+                    times.push(t);
+                } else if let Some(o) = inner.strip_prefix("offset:") {
+                    offset = o.trim().parse().unwrap_or(offset);
+                    is_tag = true;
+                } else if inner.contains(':') {
+                    // Other id tags such as `[ar:Artist]`.
+                    is_tag = true;
+                } else {
+                    // Not a tag, but part of the text (e.g. `[Chorus]`).
+                    break;
+                }
+                text = rest;
+            }
+
+            if !times.is_empty() {
+                let text = strip_word_times(text.trim());
+                timed.extend(times.into_iter().map(|t| (t, text.clone())));
+            } else if !is_tag || !text.is_empty() {
+                plain.push(text.to_string());
+            }
+        }
+
+        if timed.is_empty() {
+            let start = plain.iter().position(|l| !l.is_empty());
+            let end = plain.iter().rposition(|l| !l.is_empty());
+            let lines = match (start, end) {
+                (Some(s), Some(e)) => &plain[s..=e],
+                _ => &[],
+            };
+            return Self {
+                synced: false,
+                lines: lines
+                    .iter()
+                    .map(|l| LyricsLine {
+                        time: None,
+                        text: l.clone(),
+                    })
+                    .collect(),
+            };
+        }
+
+        timed.sort_by_key(|(t, _)| *t);
+        Self {
+            synced: true,
+            lines: timed
+                .into_iter()
+                .map(|(t, text)| LyricsLine {
+                    time: Some(apply_offset(t, offset)),
+                    text,
+                })
+                .collect(),
+        }
+    }
+
+    /// Gets the index of the line that is sung at the given time. Returns
+    /// [`None`] if the lyrics are not synced or if the first line haven't
+    /// started yet.
+    pub fn line_at(&self, t: Duration) -> Option<usize> {
+        if !self.synced {
+            return None;
+        }
+
+        self.lines
+            .partition_point(|l| l.time.is_some_and(|lt| lt <= t))
+            .checked_sub(1)
+    }
+
+    /// Gets the time when the next line after the given time starts.
+    pub fn next_time(&self, t: Duration) -> Option<Duration> {
+        self.lines.iter().filter_map(|l| l.time).find(|lt| *lt > t)
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+impl Lyrics {
+    /// Reads the lyrics from the data of id3v2 `SYLT` frame.
+    fn from_sylt(data: &[u8]) -> Option<Self> {
+        let frame =
+            SynchronizedTextFrame::parse(data, FrameFlags::default()).ok()?;
+        // Timestamps in MPEG frames would require the frame rate.
+        if frame.timestamp_format != TimestampFormat::MS
+            || !matches!(
+                frame.content_type,
+                SyncTextContentType::Lyrics
+                    | SyncTextContentType::TextTranscription
+            )
+        {
+            return None;
+        }
+
+        let mut lines: Vec<_> = frame
+            .content
+            .into_iter()
+            .map(|(t, text)| LyricsLine {
+                time: Some(Duration::from_millis(t.into())),
+                // Lines usually start with new line.
+                text: text.trim().to_string(),
+            })
+            .collect();
+        lines.sort_by_key(|l| l.time);
+
+        Some(Self {
+            synced: true,
+            lines,
+        })
+    }
+}
+
+/// Parses timestamp of lrc line in the form `mm:ss.xx`.
+fn parse_time(s: &str) -> Option<Duration> {
+    if !s.starts_with(|c: char| c.is_ascii_digit()) || !s.contains(':') {
+        return None;
+    }
+    str_to_duration(s).ok()
+}
+
+/// Removes the word timestamps (`<mm:ss.xx>`) of enhanced lrc.
+fn strip_word_times(s: &str) -> String {
+    let mut res = String::new();
+    let mut rest = s;
+    while let Some((pre, post)) = rest.split_once('<') {
+        res += pre;
+        match post.split_once('>') {
+            Some((t, post)) if parse_time(t).is_some() => rest = post,
+            _ => {
+                res.push('<');
+                rest = post;
+            }
+        }
+    }
+    res += rest;
+    res.trim().to_string()
+}
+
+fn apply_offset(t: Duration, offset: i64) -> Duration {
+    let o = Duration::from_millis(offset.unsigned_abs());
+    if offset >= 0 {
+        t.saturating_sub(o)
+    } else {
+        t + o
+    }
+}
@@ -3,15 +3,26 @@ mod album;
 mod album_id;
 mod artist;
 mod artist_id;
+mod bookmark;
+mod chapter;
+mod cue_sheet;
 pub mod img_lookup;
 mod json;
 mod library_load_result;
 mod library_msg;
 mod library_struct;
 mod library_update;
+mod library_watch;
 mod load_opts;
+mod lyrics;
+mod playlist_file;
+mod replay_gain;
+mod search_index;
 mod song;
+mod song_edit;
 mod song_id;
+mod song_range;
+mod symph_tags;
 mod tag;
 mod tags;
 
@@ -20,7 +31,9 @@ mod tags;
 //===========================================================================//
 
 pub use self::{
-    album::*, album_id::*, artist::*, artist_id::*, library_load_result::*,
-    library_struct::*, library_update::*, load_opts::*, song::*, song_id::*,
-    tag::*, tags::*,
+    album::*, album_id::*, artist::*, artist_id::*, bookmark::*, chapter::*,
+    cue_sheet::*, library_load_result::*, library_struct::*,
+    library_update::*, load_opts::*, lyrics::*, playlist_file::*,
+    replay_gain::*, search_index::*, song::*, song_edit::*, song_id::*,
+    song_range::*, tag::*, tags::*,
 };
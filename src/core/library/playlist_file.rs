@@ -0,0 +1,403 @@
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use log::warn;
+use pareg::{ArgError, FromArg};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::core::{Error, Result};
+
+use super::Song;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Entry of playlist file. Tracks of audio files split by cue sheet are
+/// referenced by the path to the cue sheet and the track number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    /// Path to the audio file or cue sheet.
+    pub path: PathBuf,
+    /// Number of the track in the cue sheet. [`None`] if this is not track
+    /// of cue sheet or if the entry refers to all the tracks.
+    pub track: Option<u32>,
+}
+
+/// Format of playlist file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlaylistFormat {
+    /// Extended M3U. Written in UTF-8, read also as Latin-1.
+    M3u,
+    /// Extended M3U in UTF-8.
+    M3u8,
+    /// XML Shareable Playlist Format.
+    Xspf,
+}
+
+impl PlaylistFormat {
+    /// Determines the playlist format from the extension of the path.
+    ///
+    /// # Errors
+    /// - The extension doesn't correspond to any known playlist format.
+    pub fn from_path(p: impl AsRef<Path>) -> Result<Self> {
+        let p = p.as_ref();
+        let ext = p
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("m3u") => Ok(Self::M3u),
+            Some("m3u8") => Ok(Self::M3u8),
+            Some("xspf") => Ok(Self::Xspf),
+            _ => Error::invalid_value()
+                .msg(format!("Unknown playlist format of `{}`.", p.display()))
+                .hint("Supported extensions are `m3u`, `m3u8` and `xspf`.")
+                .err(),
+        }
+    }
+
+    /// Gets the mime type of the format.
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Self::M3u => "audio/x-mpegurl",
+            Self::M3u8 => "application/vnd.apple.mpegurl",
+            Self::Xspf => "application/xspf+xml",
+        }
+    }
+
+    /// Gets the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::M3u => "m3u",
+            Self::M3u8 => "m3u8",
+            Self::Xspf => "xspf",
+        }
+    }
+
+    /// Writes the songs as playlist in this format.
+    pub fn write<'a>(
+        &self,
+        w: &mut impl Write,
+        songs: impl IntoIterator<Item = &'a Song>,
+    ) -> Result<()> {
+        match self {
+            Self::M3u | Self::M3u8 => write_m3u(w, songs),
+            Self::Xspf => write_xspf(w, songs),
+        }
+    }
+
+    /// Parses entries of songs from the playlist data. Relative paths are
+    /// relative to `dir`. The paths are normalized so that they match the
+    /// paths in the library.
+    pub fn parse(&self, data: Vec<u8>, dir: &Path) -> Vec<PlaylistEntry> {
+        let res = match self {
+            Self::M3u => match String::from_utf8(data) {
+                Ok(s) => parse_m3u(&s, dir),
+                Err(e) => {
+                    // Not UTF-8, so this is probably Latin-1
+                    let s: String =
+                        e.as_bytes().iter().map(|b| *b as char).collect();
+                    parse_m3u(&s, dir)
+                }
+            },
+            Self::M3u8 => parse_m3u(&String::from_utf8_lossy(&data), dir),
+            Self::Xspf => parse_xspf(&String::from_utf8_lossy(&data), dir),
+        };
+        res.into_iter()
+            .map(|e| PlaylistEntry {
+                path: normalize_path(e.path),
+                track: e.track,
+            })
+            .collect()
+    }
+}
+
+/// Reads entries of songs from the given playlist file. The format is
+/// determined from the extension.
+///
+/// # Errors
+/// - Unknown playlist format.
+/// - Failed to read the file.
+pub fn read_playlist_file(
+    path: impl AsRef<Path>,
+) -> Result<Vec<PlaylistEntry>> {
+    let path = path.as_ref();
+    let fmt = PlaylistFormat::from_path(path)?;
+    let data = fs::read(path).map_err(|e| {
+        Error::io(e)
+            .msg(format!("Failed to read playlist `{}`.", path.display()))
+    })?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    Ok(fmt.parse(data, dir))
+}
+
+/// Writes the songs to the playlist file. The format is determined from the
+/// extension.
+///
+/// # Errors
+/// - Unknown playlist format.
+/// - Failed to write the file.
+pub fn write_playlist_file<'a>(
+    path: impl AsRef<Path>,
+    songs: impl IntoIterator<Item = &'a Song>,
+) -> Result<()> {
+    let path = path.as_ref();
+    let fmt = PlaylistFormat::from_path(path)?;
+    let mut w = BufWriter::new(File::create(path)?);
+    fmt.write(&mut w, songs)?;
+    w.flush()?;
+    Ok(())
+}
+
+impl<'a> FromArg<'a> for PlaylistFormat {
+    fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+        match arg.to_ascii_lowercase().as_str() {
+            "m3u" => Ok(Self::M3u),
+            "m3u8" => Ok(Self::M3u8),
+            "xspf" => Ok(Self::Xspf),
+            _ => ArgError::invalid_value("Unknown playlist format.", arg)
+                .hint("Expected `m3u`, `m3u8` or `xspf`.")
+                .err(),
+        }
+    }
+}
+
+impl Display for PlaylistFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+/// Prefix of url fragment with the number of track in cue sheet.
+const TRACK_FRAGMENT: &str = "track=";
+
+fn write_m3u<'a>(
+    w: &mut impl Write,
+    songs: impl IntoIterator<Item = &'a Song>,
+) -> Result<()> {
+    writeln!(w, "#EXTM3U")?;
+    for s in songs {
+        let len = s.length().map(|l| l.as_secs() as i64).unwrap_or(-1);
+        let title = s.title().map(Cow::Borrowed).unwrap_or_else(|| {
+            s.path().file_stem().unwrap_or_default().to_string_lossy()
+        });
+        let info = if s.artists().is_empty() {
+            title.into_owned()
+        } else {
+            format!("{} - {title}", s.artists_str())
+        };
+        let info = info.replace(['\n', '\r'], " ");
+        writeln!(w, "#EXTINF:{len},{info}")?;
+        // Track of cue sheet can be referenced only with url.
+        if s.range().is_some() {
+            writeln!(w, "{}", song_url(s))?;
+        } else {
+            writeln!(w, "{}", s.path().to_string_lossy())?;
+        }
+    }
+    Ok(())
+}
+
+fn write_xspf<'a>(
+    w: &mut impl Write,
+    songs: impl IntoIterator<Item = &'a Song>,
+) -> Result<()> {
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(w, r#"<playlist version="1" xmlns="http://xspf.org/ns/0/">"#)?;
+    writeln!(w, "  <trackList>")?;
+    for s in songs {
+        writeln!(w, "    <track>")?;
+        write_xml_elem(w, "location", &song_url(s))?;
+        if let Some(t) = s.title() {
+            write_xml_elem(w, "title", t)?;
+        }
+        if !s.artists().is_empty() {
+            write_xml_elem(w, "creator", &s.artists_str())?;
+        }
+        if let Some(a) = s.album() {
+            write_xml_elem(w, "album", a)?;
+        }
+        if let Some(t) = s.track() {
+            write_xml_elem(w, "trackNum", &t.to_string())?;
+        }
+        if let Some(l) = s.length() {
+            write_xml_elem(w, "duration", &l.as_millis().to_string())?;
+        }
+        writeln!(w, "    </track>")?;
+    }
+    writeln!(w, "  </trackList>")?;
+    writeln!(w, "</playlist>")?;
+    Ok(())
+}
+
+/// Gets the url of the song. Tracks of cue sheets are referenced by the cue
+/// sheet with the track number in fragment (e.g. `album.cue#track=2`).
+fn song_url(s: &Song) -> String {
+    let (path, track) = match s.range() {
+        Some(r) => (r.sheet.as_path(), Some(r.track)),
+        None => (s.path(), None),
+    };
+    let Ok(mut url) = Url::from_file_path(path) else {
+        return path.to_string_lossy().into_owned();
+    };
+    if let Some(t) = track {
+        url.set_fragment(Some(&format!("{TRACK_FRAGMENT}{t}")));
+    }
+    url.to_string()
+}
+
+fn write_xml_elem(w: &mut impl Write, name: &str, val: &str) -> Result<()> {
+    let mut esc = String::with_capacity(val.len());
+    for c in val.chars() {
+        match c {
+            '&' => esc += "&amp;",
+            '<' => esc += "&lt;",
+            '>' => esc += "&gt;",
+            '"' => esc += "&quot;",
+            '\'' => esc += "&apos;",
+            c => esc.push(c),
+        }
+    }
+    writeln!(w, "      <{name}>{esc}</{name}>")?;
+    Ok(())
+}
+
+fn parse_m3u(s: &str, dir: &Path) -> Vec<PlaylistEntry> {
+    s.trim_start_matches('\u{feff}')
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| {
+            if l.contains("://") {
+                url_to_entry(l, None)
+            } else {
+                Some(PlaylistEntry {
+                    path: dir.join(l),
+                    track: None,
+                })
+            }
+        })
+        .collect()
+}
+
+fn parse_xspf(s: &str, dir: &Path) -> Vec<PlaylistEntry> {
+    let base = dir
+        .canonicalize()
+        .ok()
+        .and_then(|d| Url::from_directory_path(d).ok());
+
+    let mut res = vec![];
+    let mut rest = s;
+    while let Some(start) = rest.find("<location>") {
+        rest = &rest[start + "<location>".len()..];
+        let Some(end) = rest.find("</location>") else {
+            break;
+        };
+        let loc = unescape_xml(rest[..end].trim());
+        rest = &rest[end..];
+        res.extend(url_to_entry(&loc, base.as_ref()));
+    }
+
+    res
+}
+
+/// Canonicalizes the path. Paths that don't exist are normalized only
+/// lexically.
+fn normalize_path(p: PathBuf) -> PathBuf {
+    if let Ok(p) = p.canonicalize() {
+        return p;
+    }
+
+    let mut res = PathBuf::new();
+    for c in p.components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    res.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                res.pop();
+            }
+            c => res.push(c),
+        }
+    }
+    res
+}
+
+fn url_to_entry(s: &str, base: Option<&Url>) -> Option<PlaylistEntry> {
+    let url = match base {
+        Some(b) => b.join(s),
+        None => Url::parse(s),
+    };
+    let url = match url {
+        Ok(u) => u,
+        Err(e) => {
+            warn!("Invalid playlist entry `{s}`: {e}");
+            return None;
+        }
+    };
+
+    if url.scheme() != "file" {
+        warn!(
+            "Unsupported playlist entry `{s}`: only local files are allowed."
+        );
+        return None;
+    }
+
+    let Ok(path) = url.to_file_path() else {
+        warn!("Invalid playlist entry `{s}`.");
+        return None;
+    };
+    let track = url
+        .fragment()
+        .and_then(|f| f.strip_prefix(TRACK_FRAGMENT))
+        .and_then(|t| t.parse().ok());
+    Some(PlaylistEntry { path, track })
+}
+
+fn unescape_xml(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(p) = rest.find('&') {
+        res += &rest[..p];
+        rest = &rest[p..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let ent = &rest[1..end];
+        let c = match ent {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            e => e
+                .strip_prefix("#x")
+                .and_then(|h| u32::from_str_radix(h, 16).ok())
+                .or_else(|| e.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        if let Some(c) = c {
+            res.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            res.push('&');
+            rest = &rest[1..];
+        }
+    }
+    res += rest;
+    res
+}
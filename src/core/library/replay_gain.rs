@@ -0,0 +1,208 @@
+use std::{fs::File, path::Path};
+
+use raplay::{SampleBufferMut, Source, reexp::SampleFormat, source::Symph};
+use serde::{Deserialize, Serialize};
+use symphonia::core::meta::StandardTag;
+
+use crate::core::{Error, LogResult, Result, config::ReplayGainMode};
+
+use super::symph_tags::read_std_tags;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Replay gain information of a song.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReplayGain {
+    /// Gain of the track in dB.
+    #[serde(default)]
+    pub track_gain: Option<f32>,
+    /// Peak amplitude of the track.
+    #[serde(default)]
+    pub track_peak: Option<f32>,
+    /// Gain of the album in dB.
+    #[serde(default)]
+    pub album_gain: Option<f32>,
+    /// Peak amplitude of the album.
+    #[serde(default)]
+    pub album_peak: Option<f32>,
+    /// True if the track gain was estimated by uamp and not read from tags.
+    #[serde(default)]
+    pub estimated: bool,
+}
+
+impl ReplayGain {
+    /// Reads the replay gain from the tags in the given file.
+    ///
+    /// # Returns
+    /// [`None`] if there are no replay gain tags.
+    ///
+    /// # Errors
+    /// - Failed to open the file.
+    /// - Symphonia doesn't support the file.
+    pub fn from_tags(p: impl AsRef<Path>) -> Result<Option<Self>> {
+        let mut res = Self::default();
+        read_std_tags(p, |t| res.read_tag(t))?;
+        Ok(res.some_if_set())
+    }
+
+    /// Updates the replay gain from the given tag if it is replay gain tag.
+    pub fn read_tag(&mut self, tag: &StandardTag) {
+        match tag {
+            StandardTag::ReplayGainTrackGain(g) => {
+                self.track_gain = parse_gain(g).or(self.track_gain);
+            }
+            StandardTag::ReplayGainTrackPeak(p) => {
+                self.track_peak = parse_gain(p).or(self.track_peak);
+            }
+            StandardTag::ReplayGainAlbumGain(g) => {
+                self.album_gain = parse_gain(g).or(self.album_gain);
+            }
+            StandardTag::ReplayGainAlbumPeak(p) => {
+                self.album_peak = parse_gain(p).or(self.album_peak);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns [`None`] if no value is set.
+    pub fn some_if_set(self) -> Option<Self> {
+        (self != Self::default()).then_some(self)
+    }
+
+    /// Reads the replay gain from the tags in the given file. If there are no
+    /// tags, it is estimated. This may take long time.
+    pub fn determine(p: impl AsRef<Path>) -> Self {
+        let p = p.as_ref();
+        if let Some(rg) = Self::from_tags(p).ok().flatten() {
+            return rg;
+        }
+
+        Self::estimate(p)
+            .or_log_with(log::Level::Warn, || {
+                format!("Failed to estimate loudness of `{}`", p.display())
+            })
+            .unwrap_or(Self {
+                estimated: true,
+                ..Default::default()
+            })
+    }
+
+    /// Estimates the loudness of the song by decoding parts of it. The
+    /// estimate is stored as track gain.
+    ///
+    /// # Errors
+    /// - Failed to open the file.
+    /// - Failed to decode the file.
+    pub fn estimate(p: impl AsRef<Path>) -> Result<Self> {
+        let mut src = Symph::try_new(File::open(p)?, &Default::default())?;
+        let Some(mut conf) = src.preferred_config() else {
+            return Error::invalid_value()
+                .msg("Cannot determine the sample rate of the song.")
+                .err();
+        };
+        conf.sample_format = SampleFormat::F32;
+        let channels = conf.channel_count.max(1) as usize;
+        src.init(&conf)?;
+
+        let block_len = (conf.sample_rate as usize * BLOCK_MS / 1000).max(1);
+        let mut buf = vec![0.; block_len * channels];
+        let total = src.get_time().map(|t| t.total);
+
+        let mut blocks = vec![];
+        let mut peak = 0_f32;
+        for seg in 0..SEGMENTS {
+            if let Some(total) = total {
+                let pos = total.mul_f32((seg as f32 + 0.5) / SEGMENTS as f32);
+                if src.seek(pos).is_err() && seg != 0 {
+                    break;
+                }
+            }
+
+            for _ in 0..SEGMENT_BLOCKS {
+                let (cnt, r) = src.read(&mut SampleBufferMut::F32(&mut buf));
+                r?;
+                if cnt == 0 {
+                    break;
+                }
+                let data = &buf[..cnt];
+                peak = data.iter().fold(peak, |p, s| p.max(s.abs()));
+                let ms = data.iter().map(|s| s * s).sum::<f32>() / cnt as f32;
+                blocks.push(ms * channels as f32);
+            }
+        }
+
+        // Ignore silence in the same way as EBU R 128 absolute gate.
+        let gate = 10_f32.powf((ABSOLUTE_GATE + 0.691) / 10.);
+        let (sum, cnt) = blocks
+            .iter()
+            .filter(|ms| **ms > gate)
+            .fold((0., 0), |(s, c), ms| (s + ms, c + 1));
+
+        let track_gain = (cnt != 0).then(|| {
+            let loudness = 10. * (sum / cnt as f32).log10() - 0.691;
+            REFERENCE_LOUDNESS - loudness
+        });
+
+        Ok(Self {
+            track_gain,
+            track_peak: (peak != 0.).then_some(peak),
+            estimated: true,
+            ..Default::default()
+        })
+    }
+
+    /// Gets the amplitude multiplier that should be applied to the song.
+    ///
+    /// - `mode`: which gain to use. If the gain is not available, the other
+    ///   gain is used.
+    /// - `preamp`: gain in dB that is added to the replay gain.
+    pub fn amplitude(&self, mode: ReplayGainMode, preamp: f32) -> f32 {
+        let (gain, peak) = match mode {
+            ReplayGainMode::Off => return 1.,
+            ReplayGainMode::Track => self
+                .track_gain
+                .map(|g| (g, self.track_peak))
+                .or_else(|| self.album_gain.map(|g| (g, self.album_peak))),
+            ReplayGainMode::Album => self
+                .album_gain
+                .map(|g| (g, self.album_peak))
+                .or_else(|| self.track_gain.map(|g| (g, self.track_peak))),
+        }
+        .unwrap_or((0., None));
+
+        let amp = 10_f32.powf((gain + preamp) / 20.);
+        // Prevent clipping.
+        match peak {
+            Some(p) if p > 0. => amp.min(1. / p),
+            _ => amp,
+        }
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+/// Loudness in LUFS to which replay gain normalizes.
+const REFERENCE_LOUDNESS: f32 = -18.;
+/// Blocks quieter than this (in LUFS) are ignored in the estimate.
+const ABSOLUTE_GATE: f32 = -70.;
+/// Length of single block in the estimate.
+const BLOCK_MS: usize = 400;
+/// Number of parts of the song that are decoded for the estimate.
+const SEGMENTS: usize = 5;
+/// Number of blocks decoded in each segment.
+const SEGMENT_BLOCKS: usize = 8;
+
+/// Parses values such as `-6.5 dB` or `0.98`.
+fn parse_gain(s: &str) -> Option<f32> {
+    let s = s.trim();
+    let s = s
+        .strip_suffix("dB")
+        .or_else(|| s.strip_suffix("db"))
+        .or_else(|| s.strip_suffix("DB"))
+        .unwrap_or(s);
+    s.trim().parse().ok().filter(|v: &f32| v.is_finite())
+}
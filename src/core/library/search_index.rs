@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::ext::simpl;
+
+use super::{Song, SongId};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Inverted index over the simplified (see [`simpl`]) text of songs.
+///
+/// Each field maps trigrams of the simplified strings to songs that contain
+/// them. Lookups return superset of the songs that contain the pattern, so
+/// the songs must still be checked with the filter. This way the index may
+/// contain stale entries and it only has to be rebuilt when songs are
+/// replaced.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    title: Postings,
+    artist: Postings,
+    album: Postings,
+    genre: Postings,
+    /// Number of songs covered by the index. Songs with larger index
+    /// (including temporary songs) are not indexed.
+    len: usize,
+}
+
+/// Field of songs that is indexed in [`SearchIndex`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SearchField {
+    Title,
+    Artist,
+    Album,
+    Genre,
+}
+
+/// Set of songs found in [`SearchIndex`]. Songs that are not indexed are
+/// always considered to be part of the set.
+#[derive(Debug, Clone)]
+pub struct SongSet {
+    /// Sorted indexes of songs.
+    ids: Vec<u32>,
+    /// Number of songs covered by the index.
+    indexed: usize,
+}
+
+impl SearchIndex {
+    /// Creates index of the given songs.
+    pub fn new(songs: &[Song]) -> Self {
+        let mut res = Self {
+            len: songs.len(),
+            ..Self::default()
+        };
+
+        let mut buf = String::new();
+        for (i, s) in songs.iter().enumerate() {
+            if !s.is_deleted() {
+                res.add_song(i, s, &mut buf);
+            }
+        }
+
+        res
+    }
+
+    /// Finds songs where the field may contain the simplified pattern.
+    /// Returns [`None`] if the pattern is too short to be searched in the
+    /// index.
+    pub fn find(&self, field: SearchField, pat: &str) -> Option<SongSet> {
+        if pat.len() < 3 {
+            return None;
+        }
+
+        let postings = self.postings(field);
+        let mut lists = vec![];
+        for t in trigrams(pat) {
+            let Some(l) = postings.get(&t) else {
+                return Some(self.set(vec![]));
+            };
+            lists.push(l);
+        }
+
+        lists.sort_by_key(|l| l.len());
+        let mut ids = lists[0].clone();
+        for l in &lists[1..] {
+            ids.retain(|i| l.binary_search(i).is_ok());
+        }
+
+        Some(self.set(ids))
+    }
+
+    /// Adds the current data of the song to the index. Old data of the song
+    /// is kept in the index.
+    pub(super) fn update_song(&mut self, id: SongId, song: &Song) {
+        let idx = id.as_norm();
+        if idx < self.len && !song.is_deleted() {
+            self.add_song(idx, song, &mut String::new());
+        }
+    }
+}
+
+impl SongSet {
+    /// Checks if the song may be in the set.
+    pub fn contains(&self, id: SongId) -> bool {
+        let idx = id.as_norm();
+        idx >= self.indexed || self.ids.binary_search(&(idx as u32)).is_ok()
+    }
+
+    /// Songs that are in both sets.
+    pub fn intersect(mut self, other: &Self) -> Self {
+        self.ids.retain(|i| other.ids.binary_search(i).is_ok());
+        self
+    }
+
+    /// Songs that are in either of the sets.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            ids: self.ids.into_iter().merge(other.ids).dedup().collect(),
+            indexed: self.indexed,
+        }
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+type Postings = HashMap<[u8; 3], Vec<u32>>;
+
+impl SearchIndex {
+    fn add_song(&mut self, idx: usize, song: &Song, buf: &mut String) {
+        let idx = idx as u32;
+        add_str(&mut self.title, idx, song.title(), buf);
+        for a in song.artists() {
+            add_str(&mut self.artist, idx, Some(a), buf);
+        }
+        add_str(&mut self.album, idx, song.album(), buf);
+        for g in song.genres() {
+            add_str(&mut self.genre, idx, Some(g), buf);
+        }
+    }
+
+    fn postings(&self, field: SearchField) -> &Postings {
+        match field {
+            SearchField::Title => &self.title,
+            SearchField::Artist => &self.artist,
+            SearchField::Album => &self.album,
+            SearchField::Genre => &self.genre,
+        }
+    }
+
+    fn set(&self, ids: Vec<u32>) -> SongSet {
+        SongSet {
+            ids,
+            indexed: self.len,
+        }
+    }
+}
+
+fn add_str(p: &mut Postings, idx: u32, s: Option<&str>, buf: &mut String) {
+    let Some(s) = s else {
+        return;
+    };
+
+    buf.clear();
+    simpl::to_str(s, buf);
+    for t in trigrams(buf) {
+        let ids = p.entry(t).or_default();
+        match ids.last() {
+            // Songs are usually added in order, so this is fast path.
+            Some(l) if *l >= idx => {
+                if let Err(i) = ids.binary_search(&idx) {
+                    ids.insert(i, idx);
+                }
+            }
+            _ => ids.push(idx),
+        }
+    }
+}
+
+fn trigrams(s: &str) -> impl Iterator<Item = [u8; 3]> + '_ {
+    s.as_bytes().windows(3).map(|w| [w[0], w[1], w[2]])
+}
@@ -2,25 +2,32 @@ use std::{
     borrow::Cow,
     collections::BTreeSet,
     fmt::Debug,
-    fs::File,
+    fs::{self, File},
+    mem,
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use itertools::Itertools;
 use raplay::{Source, source::Symph};
 use ratag::{DataType, TagStore, read_tag_from_file, trap};
 use serde::{Deserialize, Serialize};
+use symphonia::core::meta::StandardTag;
 
 use crate::{
     core::{
-        LogResult, Result,
+        Error, LogResult, Result,
         config::{CacheSize, Config},
     },
     ext::duration_to_string,
 };
 
+use super::{
+    Bookmark, Chapter, CueSheet, CueTrack, ReplayGain, SongEdit, SongRange,
+    symph_tags::read_std_tags_chapters,
+};
+
 //===========================================================================//
 //                                   Public                                  //
 //===========================================================================//
@@ -38,10 +45,40 @@ pub struct Song {
     pub(super) album: Option<Arc<str>>,
     /// Artist responsible for the album.
     pub(super) album_artist: Option<Arc<str>>,
+    /// Title used when sorting (e.g. without articles).
+    #[serde(default)]
+    pub(super) title_sort: Option<Arc<str>>,
+    /// Artist used when sorting (e.g. `Beatles, The`).
+    #[serde(default)]
+    pub(super) artist_sort: Option<Arc<str>>,
+    /// Album used when sorting.
+    #[serde(default)]
+    pub(super) album_sort: Option<Arc<str>>,
+    /// Album artist used when sorting.
+    #[serde(default)]
+    pub(super) album_artist_sort: Option<Arc<str>>,
+    /// Composers of the song.
+    #[serde(default)]
+    pub(super) composers: Vec<Arc<str>>,
+    /// Conductors of the song.
+    #[serde(default)]
+    pub(super) conductors: Vec<Arc<str>>,
+    /// Performers of the song other than the main artists.
+    #[serde(default)]
+    pub(super) performers: Vec<Arc<str>>,
+    /// Lyricists of the song.
+    #[serde(default)]
+    pub(super) lyricists: Vec<Arc<str>>,
     /// The track number in the album.
     pub(super) track: Option<u32>,
     /// The disc number in the album.
     pub(super) disc: Option<u32>,
+    /// Total number of discs in the album.
+    #[serde(default)]
+    pub(super) disc_count: Option<u32>,
+    /// Subtitle of the disc.
+    #[serde(default)]
+    pub(super) disc_subtitle: Option<Arc<str>>,
     /// The year of release.
     pub(super) year: Option<i32>,
     /// The duration/length of the track.
@@ -55,6 +92,44 @@ pub struct Song {
     /// all cases, and should be removed from all collections.
     #[serde(default = "default_deleted")]
     pub(super) deleted: bool,
+    /// Number of times the song was played.
+    #[serde(default)]
+    pub(super) play_count: u32,
+    /// Number of times the song was skipped.
+    #[serde(default)]
+    pub(super) skip_count: u32,
+    /// Time when the song was last played as unix timestamp in seconds.
+    #[serde(default)]
+    pub(super) last_played: Option<u64>,
+    /// Rating of the song from 0 to [`Song::MAX_RATING`]. [`None`] if the
+    /// song is not rated.
+    #[serde(default)]
+    pub(super) rating: Option<u8>,
+    /// Position where the playback of the song should resume. [`None`] if
+    /// the song should play from the start.
+    #[serde(default)]
+    pub(super) resume_pos: Option<Duration>,
+    /// Replay gain of the song. [`None`] if it wasn't determined yet.
+    #[serde(default)]
+    pub(super) replay_gain: Option<ReplayGain>,
+    /// Modification time of the file as unix timestamp in seconds when the
+    /// song was loaded.
+    #[serde(default)]
+    pub(super) modified: Option<u64>,
+    /// Size of the file in bytes when the song was loaded.
+    #[serde(default)]
+    pub(super) file_size: Option<u64>,
+    /// Part of the file that is the song. It is set for songs from cue
+    /// sheets.
+    #[serde(default)]
+    pub(super) range: Option<SongRange>,
+    /// Chapters of the song read from its metadata. They are ordered by
+    /// their start.
+    #[serde(default)]
+    pub(super) chapters: Vec<Chapter>,
+    /// Bookmarks added by the user. They are ordered by their time.
+    #[serde(default)]
+    pub(super) bookmarks: Vec<Bookmark>,
 }
 
 struct SongTagReader<'a> {
@@ -62,16 +137,43 @@ struct SongTagReader<'a> {
 }
 
 impl Song {
+    /// Maximum rating of a song.
+    pub const MAX_RATING: u8 = 5;
+
     /// Creates song from the given path
     pub fn from_path(
         path: impl AsRef<Path>,
         tags: BTreeSet<Arc<str>>,
     ) -> Result<Self> {
         let mut res = Self::empty(path.as_ref());
+        (res.modified, res.file_size) = file_stamp(path.as_ref());
         SongTagReader::read_to(&mut res, path.as_ref())?;
         res.artists = res.artists.into_iter().unique().collect();
         res.genres = res.genres.into_iter().unique().collect();
         res.tags = tags;
+        // Files not supported by symphonia may be supported by plugins, so
+        // failure to read the tags is not error.
+        let mut rg = ReplayGain::default();
+        let mut r = SongTagReader::new(&mut res);
+        let chapters = read_std_tags_chapters(path.as_ref(), |t| {
+            rg.read_tag(t);
+            r.read_std_tag(t);
+        });
+        res.replay_gain = rg.some_if_set();
+        res.chapters = match chapters {
+            Ok(c) if !c.is_empty() => c,
+            _ => Chapter::read_mp4(path.as_ref())
+                .or_log_err("Failed to read mp4 chapters.")
+                .unwrap_or_default(),
+        };
+        for roles in [
+            &mut res.composers,
+            &mut res.conductors,
+            &mut res.performers,
+            &mut res.lyricists,
+        ] {
+            *roles = mem::take(roles).into_iter().unique().collect();
+        }
         if res.length().is_none() {
             res.length = symph_get_len(path)
                 .or_log_err("Failed to get song time.")
@@ -80,6 +182,79 @@ impl Song {
         Ok(res)
     }
 
+    /// Creates songs from the tracks of the cue sheet at the given path.
+    /// Tracks of audio files that fail to load are skipped.
+    ///
+    /// # Errors
+    /// - Failed to read all of the audio files of the cue sheet.
+    pub fn from_cue(
+        path: impl AsRef<Path>,
+        sheet: &CueSheet,
+        tags: BTreeSet<Arc<str>>,
+    ) -> Result<Vec<Self>> {
+        let path = path.as_ref();
+        let (modified, file_size) = file_stamp(path);
+        let mut res = vec![];
+        let mut errs = vec![];
+
+        for file in &sheet.files {
+            let base = match Self::from_path(&file.path, tags.clone()) {
+                Ok(s) => s,
+                Err(e) => {
+                    errs.push(e.prepend(format!(
+                        "Failed to load tracks of `{}` from cue sheet.",
+                        file.path.display()
+                    )));
+                    continue;
+                }
+            };
+            for (i, track) in file.tracks.iter().enumerate() {
+                let range = SongRange {
+                    sheet: path.to_owned(),
+                    track: track.number,
+                    start: track.start,
+                    end: file.tracks.get(i + 1).map(|t| t.start),
+                    modified,
+                    file_size,
+                };
+                res.push(base.cue_track(sheet, track, range));
+            }
+        }
+
+        if res.is_empty() {
+            Error::multiple(errs)?;
+        } else {
+            Error::multiple(errs).or_warn("Skipping tracks of cue sheet.");
+        }
+        Ok(res)
+    }
+
+    /// Reads the song again from its file. Data that is not stored in the
+    /// file (statistics and tags) is kept.
+    ///
+    /// Songs from cue sheets should be reloaded with [`Song::from_cue`] and
+    /// [`Song::inherit`].
+    pub fn reload(&self) -> Result<Self> {
+        let mut res = Self::from_path(&self.path, self.tags.clone())?;
+        res.inherit(self);
+        Ok(res)
+    }
+
+    /// Takes the data that is not stored in the file (statistics and tags)
+    /// from the old version of this song.
+    pub fn inherit(&mut self, old: &Song) {
+        self.tags = old.tags.clone();
+        self.play_count = old.play_count;
+        self.skip_count = old.skip_count;
+        self.last_played = old.last_played;
+        self.rating = old.rating;
+        self.resume_pos = old.resume_pos;
+        self.bookmarks = old.bookmarks.clone();
+        if self.replay_gain.is_none() {
+            self.replay_gain = old.replay_gain.filter(|r| r.estimated);
+        }
+    }
+
     pub fn get_cached_path(
         &self,
         conf: &Config,
@@ -108,13 +283,34 @@ impl Song {
             artists: vec![],
             album: None,
             album_artist: None,
+            title_sort: None,
+            artist_sort: None,
+            album_sort: None,
+            album_artist_sort: None,
+            composers: vec![],
+            conductors: vec![],
+            performers: vec![],
+            lyricists: vec![],
             track: None,
             disc: None,
+            disc_count: None,
+            disc_subtitle: None,
             year: None,
             length: None,
             genres: vec![],
             tags: BTreeSet::new(),
             deleted: false,
+            play_count: 0,
+            skip_count: 0,
+            last_played: None,
+            rating: None,
+            resume_pos: None,
+            replay_gain: None,
+            modified: None,
+            file_size: None,
+            range: None,
+            chapters: vec![],
+            bookmarks: vec![],
         }
     }
 
@@ -140,6 +336,53 @@ impl Song {
         }
     }
 
+    /// Gets the artist used when sorting if it is tagged.
+    pub fn artist_sort(&self) -> Option<&str> {
+        self.artist_sort.as_deref()
+    }
+
+    /// Gets the title used when sorting. Falls back to the title.
+    pub fn sort_title(&self) -> Option<&str> {
+        self.title_sort.as_deref().or(self.title())
+    }
+
+    /// Gets the album used when sorting. Falls back to the album.
+    pub fn sort_album(&self) -> Option<&str> {
+        self.album_sort.as_deref().or(self.album())
+    }
+
+    /// Gets the album artist used when sorting. Falls back to the album
+    /// artist.
+    pub fn sort_album_artist(&self) -> Option<&str> {
+        if let Some(a) = &self.album_artist_sort {
+            Some(a)
+        } else if self.album_artist.is_none() && self.artist_sort.is_some() {
+            self.artist_sort()
+        } else {
+            self.album_artist()
+        }
+    }
+
+    /// Gets the composers of the song.
+    pub fn composers(&self) -> &[Arc<str>] {
+        &self.composers
+    }
+
+    /// Gets the conductors of the song.
+    pub fn conductors(&self) -> &[Arc<str>] {
+        &self.conductors
+    }
+
+    /// Gets the performers of the song other than the main artists.
+    pub fn performers(&self) -> &[Arc<str>] {
+        &self.performers
+    }
+
+    /// Gets the lyricists of the song.
+    pub fn lyricists(&self) -> &[Arc<str>] {
+        &self.lyricists
+    }
+
     /// Gets the album of the song.
     pub fn album(&self) -> Option<&str> {
         self.album.as_deref()
@@ -168,6 +411,18 @@ impl Song {
         &self.path
     }
 
+    /// Gets the part of the file that is the song. It is set for songs from
+    /// cue sheets.
+    pub fn range(&self) -> Option<&SongRange> {
+        self.range.as_ref()
+    }
+
+    /// Checks whether the file of the song (and its cue sheet) exists.
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+            && self.range.as_ref().is_none_or(|r| r.sheet.exists())
+    }
+
     /// Gets the track number of the song in the album.
     pub fn track(&self) -> Option<u32> {
         self.track
@@ -192,6 +447,16 @@ impl Song {
             .unwrap_or("--".into())
     }
 
+    /// Gets the total number of discs in the album.
+    pub fn disc_count(&self) -> Option<u32> {
+        self.disc_count
+    }
+
+    /// Gets the subtitle of the disc of this song.
+    pub fn disc_subtitle(&self) -> Option<&str> {
+        self.disc_subtitle.as_deref()
+    }
+
     /// Returns true if the song is deleted
     pub fn is_deleted(&self) -> bool {
         self.deleted
@@ -239,6 +504,190 @@ impl Song {
         &self.tags
     }
 
+    /// Gets the number of times the song was played.
+    pub fn play_count(&self) -> u32 {
+        self.play_count
+    }
+
+    /// Gets the number of times the song was skipped.
+    pub fn skip_count(&self) -> u32 {
+        self.skip_count
+    }
+
+    /// Gets the time when the song was last played as unix timestamp in
+    /// seconds.
+    pub fn last_played(&self) -> Option<u64> {
+        self.last_played
+    }
+
+    /// Records that the song was played now.
+    pub fn mark_played(&mut self) {
+        self.play_count += 1;
+        self.last_played = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+    }
+
+    /// Gets the rating of the song. [`None`] if the song is not rated.
+    pub fn rating(&self) -> Option<u8> {
+        self.rating
+    }
+
+    /// Sets the rating of the song. The rating is clamped to
+    /// [`Song::MAX_RATING`].
+    pub fn set_rating(&mut self, rating: Option<u8>) {
+        self.rating = rating.map(|r| r.min(Self::MAX_RATING));
+    }
+
+    /// Gets the position where the playback of the song should resume.
+    pub fn resume_pos(&self) -> Option<Duration> {
+        self.resume_pos
+    }
+
+    /// Sets the position where the playback of the song should resume.
+    /// [`None`] means that the song plays from the start.
+    pub fn set_resume_pos(&mut self, pos: Option<Duration>) {
+        self.resume_pos = pos;
+    }
+
+    /// Gets the chapters of the song ordered by their start.
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    /// Gets the index of the chapter that plays at the given time.
+    pub fn chapter_at(&self, t: Duration) -> Option<usize> {
+        self.chapters
+            .partition_point(|c| c.start <= t)
+            .checked_sub(1)
+    }
+
+    /// Gets the bookmarks of the song ordered by their time.
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// Adds the bookmark to the song. Bookmark with the same name is
+    /// replaced.
+    pub fn add_bookmark(&mut self, bookmark: Bookmark) {
+        self.remove_bookmark(&bookmark.name);
+        let idx = self.bookmarks.partition_point(|b| b.time <= bookmark.time);
+        self.bookmarks.insert(idx, bookmark);
+    }
+
+    /// Removes all bookmarks with the given name. Returns `true` if any
+    /// bookmark was removed.
+    pub fn remove_bookmark(&mut self, name: &str) -> bool {
+        let len = self.bookmarks.len();
+        self.bookmarks.retain(|b| b.name != name);
+        self.bookmarks.len() != len
+    }
+
+    /// Records that the song was skipped.
+    pub fn mark_skipped(&mut self) {
+        self.skip_count += 1;
+    }
+
+    /// Gets the replay gain of the song. [`None`] if it wasn't determined
+    /// yet.
+    pub fn replay_gain(&self) -> Option<&ReplayGain> {
+        self.replay_gain.as_ref()
+    }
+
+    /// Sets the replay gain of the song.
+    pub fn set_replay_gain(&mut self, rg: ReplayGain) {
+        self.replay_gain = Some(rg);
+    }
+
+    /// Checks whether the file of the song has changed since the song was
+    /// loaded. Songs where the file doesn't exist or where the stamp of the
+    /// file is unknown are not changed.
+    pub fn file_changed(&self) -> bool {
+        if !self.path.exists() {
+            return false;
+        }
+        stamp_changed(&self.path, (self.modified, self.file_size))
+            || self.range.as_ref().is_some_and(|r| {
+                stamp_changed(&r.sheet, (r.modified, r.file_size))
+            })
+    }
+
+    /// Checks whether the modification time and size of the file is known.
+    /// Songs loaded by older versions of uamp don't have it.
+    pub fn has_file_stamp(&self) -> bool {
+        (self.modified, self.file_size) != (None, None)
+            && self
+                .range
+                .as_ref()
+                .is_none_or(|r| (r.modified, r.file_size) != (None, None))
+    }
+
+    /// Sets the unknown modification time and size of the file. Returns
+    /// `true` if it was unknown.
+    pub fn fill_file_stamp(&mut self) -> bool {
+        if self.has_file_stamp() {
+            return false;
+        }
+        if (self.modified, self.file_size) == (None, None) {
+            self.update_file_stamp();
+        }
+        if let Some(r) = &mut self.range
+            && (r.modified, r.file_size) == (None, None)
+        {
+            (r.modified, r.file_size) = file_stamp(&r.sheet);
+        }
+        true
+    }
+
+    /// Updates the stored modification time and size of the file so that
+    /// the file is no longer considered changed.
+    pub fn update_file_stamp(&mut self) {
+        (self.modified, self.file_size) = file_stamp(&self.path);
+    }
+
+    /// Applies the given changes to the metadata of the song.
+    pub fn apply_edit(&mut self, edit: &SongEdit) {
+        fn non_empty<T: AsRef<str>>(s: &T) -> bool {
+            !s.as_ref().is_empty()
+        }
+
+        if let Some(t) = &edit.title {
+            self.title = Some(t.clone()).filter(non_empty);
+            self.title_sort = None;
+        }
+        if let Some(a) = &edit.artists {
+            self.artist_sort = None;
+            self.artists = a
+                .iter()
+                .filter(non_empty)
+                .unique()
+                .map(|a| a.as_str().into())
+                .collect();
+        }
+        if let Some(a) = &edit.album {
+            self.album = Some(a.as_str().into()).filter(non_empty);
+            self.album_sort = None;
+        }
+        if let Some(a) = &edit.album_artist {
+            self.album_artist = Some(a.as_str().into()).filter(non_empty);
+            self.album_artist_sort = None;
+        }
+        if let Some(t) = edit.track {
+            self.track = Some(t).filter(|t| *t != 0);
+        }
+        if let Some(d) = edit.disc {
+            self.disc = Some(d).filter(|d| *d != 0);
+        }
+        if let Some(y) = edit.year {
+            self.year = Some(y).filter(|y| *y != 0);
+        }
+        if let Some(g) = &edit.genres {
+            self.genres =
+                g.iter().filter(non_empty).unique().cloned().collect();
+        }
+    }
+
     pub fn genres_str(&self) -> Cow<'_, str> {
         match self.genres.as_slice() {
             [] => "--".into(),
@@ -252,6 +701,61 @@ impl Song {
 //                                  Private                                  //
 //===========================================================================//
 
+impl Song {
+    /// Creates song from the track of cue sheet. This song is the song of
+    /// the whole audio file.
+    fn cue_track(
+        &self,
+        sheet: &CueSheet,
+        track: &CueTrack,
+        range: SongRange,
+    ) -> Self {
+        let mut res = self.clone();
+        res.title = track.title.clone();
+        res.title_sort = None;
+        if let Some(p) = track.performer.as_ref().or(sheet.performer.as_ref())
+        {
+            res.artists = vec![p.as_str().into()];
+            res.artist_sort = None;
+        }
+        if let Some(a) = &sheet.title {
+            res.album = Some(a.as_str().into());
+            res.album_sort = None;
+        }
+        if let Some(a) = &sheet.performer {
+            res.album_artist = Some(a.as_str().into());
+            res.album_artist_sort = None;
+        }
+        if let Some(c) = &track.songwriter {
+            res.composers = vec![c.as_str().into()];
+        }
+        res.track = Some(track.number);
+        res.disc = sheet.disc.or(res.disc);
+        res.disc_count = sheet.disc_count.or(res.disc_count);
+        res.year = sheet.year.or(res.year);
+        if let Some(g) = &sheet.genre {
+            res.genres = vec![g.clone()];
+        }
+        res.length = range
+            .end
+            .or(self.length)
+            .map(|e| e.saturating_sub(range.start));
+        res.chapters = self
+            .chapters
+            .iter()
+            .filter(|c| {
+                c.start >= range.start && range.end.is_none_or(|e| c.start < e)
+            })
+            .map(|c| Chapter {
+                start: c.start - range.start,
+                title: c.title.clone(),
+            })
+            .collect();
+        res.range = Some(range);
+        res
+    }
+}
+
 impl<'a> SongTagReader<'a> {
     pub fn new(s: &'a mut Song) -> Self {
         Self { song: s }
@@ -264,6 +768,41 @@ impl<'a> SongTagReader<'a> {
             r => r.map_err(|e| e.into()),
         }
     }
+
+    /// Reads data that is not supported by ratag from symphonia tag.
+    pub fn read_std_tag(&mut self, tag: &StandardTag) {
+        let (roles, name) = match tag {
+            StandardTag::DiscSubtitle(s) if !s.is_empty() => {
+                self.song.disc_subtitle = Some(s.as_str().into());
+                return;
+            }
+            StandardTag::SortTrackTitle(s) if !s.is_empty() => {
+                self.song.title_sort = Some(s.as_str().into());
+                return;
+            }
+            StandardTag::SortArtist(s) if !s.is_empty() => {
+                self.song.artist_sort = Some(s.as_str().into());
+                return;
+            }
+            StandardTag::SortAlbum(s) if !s.is_empty() => {
+                self.song.album_sort = Some(s.as_str().into());
+                return;
+            }
+            StandardTag::SortAlbumArtist(s) if !s.is_empty() => {
+                self.song.album_artist_sort = Some(s.as_str().into());
+                return;
+            }
+            StandardTag::Composer(n) => (&mut self.song.composers, n),
+            StandardTag::Conductor(n) => (&mut self.song.conductors, n),
+            StandardTag::Performer(n) => (&mut self.song.performers, n),
+            StandardTag::Lyricist(n) => (&mut self.song.lyricists, n),
+            _ => return,
+        };
+
+        if !name.is_empty() {
+            roles.push(name.as_str().into());
+        }
+    }
 }
 
 impl<'a> TagStore for SongTagReader<'a> {
@@ -276,6 +815,7 @@ impl<'a> TagStore for SongTagReader<'a> {
                 | DataType::AlbumArtist
                 | DataType::Track
                 | DataType::Disc
+                | DataType::DiscCount
                 | DataType::Year
                 | DataType::Length
                 | DataType::Genres
@@ -319,6 +859,12 @@ impl<'a> TagStore for SongTagReader<'a> {
         }
     }
 
+    fn set_disc_count(&mut self, cnt: u32) {
+        if cnt != 0 {
+            self.song.disc_count = Some(cnt);
+        }
+    }
+
     fn set_year(&mut self, year: i32) {
         self.song.year = Some(year);
     }
@@ -338,6 +884,26 @@ fn default_deleted() -> bool {
     false
 }
 
+/// Gets the modification time as unix timestamp in seconds and the size of
+/// the file.
+fn file_stamp(p: &Path) -> (Option<u64>, Option<u64>) {
+    let Ok(meta) = fs::metadata(p) else {
+        return (None, None);
+    };
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+    (modified, Some(meta.len()))
+}
+
+/// Checks whether the stamp of the file differs from the given stamp.
+/// Unknown stamp is never changed.
+fn stamp_changed(p: &Path, stamp: (Option<u64>, Option<u64>)) -> bool {
+    stamp != (None, None) && file_stamp(p) != stamp
+}
+
 fn symph_get_len(p: impl AsRef<Path>) -> Result<Option<Duration>> {
     let s = Symph::try_new(File::open(p)?, &Default::default())?;
     Ok(s.get_time().map(|t| t.total))
@@ -0,0 +1,317 @@
+use std::{fmt::Display, mem, path::Path, str::FromStr};
+
+use itertools::Itertools;
+use lofty::{
+    config::WriteOptions,
+    file::{AudioFile, TaggedFileExt},
+    tag::{Accessor, ItemKey, ItemValue, Tag, TagItem, items::Timestamp},
+};
+use pareg::{ArgError, FromArgStr};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Error, Result};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Changes to the metadata of songs. Fields that are [`None`] are not
+/// changed. Empty strings, empty lists and zeros remove the value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SongEdit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artists: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disc: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genres: Option<Vec<String>>,
+}
+
+impl SongEdit {
+    /// Checks whether the edit doesn't change anything.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Parses the edit from the start of the string until the first unquoted
+    /// `:`.
+    ///
+    /// # Returns
+    /// The edit and the rest of the string after the `:`. The rest is
+    /// [`None`] if there is no unquoted `:`.
+    pub fn parse_prefix(s: &str) -> pareg::Result<(Self, Option<&str>)> {
+        let mut res = Self::default();
+        let mut chars = s.char_indices().peekable();
+        let mut quoted = false;
+        let mut key = String::new();
+        let mut value = None;
+        let mut items = vec![];
+        let mut rest = None;
+
+        while let Some((i, c)) = chars.next() {
+            let in_value = value.is_some();
+            let buf = value.as_mut().unwrap_or(&mut key);
+            match c {
+                '/' if quoted
+                    && chars.next_if(|(_, c)| *c == '/').is_some() =>
+                {
+                    buf.push('/')
+                }
+                '/' => quoted = !quoted,
+                _ if quoted => buf.push(c),
+                '=' if !in_value => value = Some(String::new()),
+                ';' if in_value => items.push(mem::take(buf)),
+                ',' | ':' => {
+                    items.extend(value.take());
+                    res.set(s, &key, mem::take(&mut items))?;
+                    key.clear();
+                    if c == ':' {
+                        rest = Some(&s[i + 1..]);
+                        break;
+                    }
+                }
+                _ => buf.push(c),
+            }
+        }
+
+        if quoted {
+            return ArgError::failed_to_parse("Expected ending `/`.", s).err();
+        }
+
+        if rest.is_none() && (!key.is_empty() || value.is_some()) {
+            items.extend(value.take());
+            res.set(s, &key, items)?;
+        }
+
+        Ok((res, rest))
+    }
+
+    /// Writes the changes to the tags of the given audio file.
+    ///
+    /// # Errors
+    /// - The file format is not supported.
+    /// - Failed to read or write the file.
+    pub fn write_to(&self, p: impl AsRef<Path>) -> Result<()> {
+        let mut file = lofty::read_from_path(p.as_ref())?;
+        if file.primary_tag().is_none() {
+            file.insert_tag(Tag::new(file.primary_tag_type()));
+        }
+        let Some(tag) = file.primary_tag_mut() else {
+            return Error::unexpected()
+                .msg("Failed to create tag in the file.")
+                .err();
+        };
+
+        // Sort names would no longer match the changed values.
+        if let Some(t) = &self.title {
+            tag.remove_key(ItemKey::TrackTitleSortOrder);
+            if t.is_empty() {
+                tag.remove_title();
+            } else {
+                tag.set_title(t.clone());
+            }
+        }
+        if let Some(a) = &self.artists {
+            tag.remove_key(ItemKey::TrackArtistSortOrder);
+            set_multiple(tag, ItemKey::TrackArtist, a);
+        }
+        if let Some(a) = &self.album {
+            tag.remove_key(ItemKey::AlbumTitleSortOrder);
+            if a.is_empty() {
+                tag.remove_album();
+            } else {
+                tag.set_album(a.clone());
+            }
+        }
+        if let Some(a) = &self.album_artist {
+            tag.remove_key(ItemKey::AlbumArtist);
+            tag.remove_key(ItemKey::AlbumArtistSortOrder);
+            if !a.is_empty() {
+                tag.insert_text(ItemKey::AlbumArtist, a.clone());
+            }
+        }
+        match self.track {
+            Some(0) => tag.remove_track(),
+            Some(t) => tag.set_track(t),
+            None => {}
+        }
+        match self.disc {
+            Some(0) => tag.remove_disk(),
+            Some(d) => tag.set_disk(d),
+            None => {}
+        }
+        if let Some(y) = self.year {
+            // Some formats (ID3v2.4) distinguish between recording and
+            // release date. Set both so that the year is read back no matter
+            // which one is preferred.
+            tag.remove_date();
+            tag.remove_key(ItemKey::ReleaseDate);
+            if y != 0 {
+                let date = Timestamp {
+                    year: y.try_into().map_err(|_| {
+                        Error::invalid_value().msg(format!(
+                            "Cannot write year `{y}` to the tag."
+                        ))
+                    })?,
+                    ..Default::default()
+                };
+                tag.set_date(date);
+                tag.insert_text(ItemKey::ReleaseDate, date.to_string());
+            }
+        }
+        if let Some(g) = &self.genres {
+            set_multiple(tag, ItemKey::Genre, g);
+        }
+
+        file.save_to_path(p, WriteOptions::default())?;
+        Ok(())
+    }
+}
+
+impl Display for SongEdit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut fields = vec![];
+        if let Some(t) = &self.title {
+            fields.push(format!("n={}", quote(t)));
+        }
+        if let Some(a) = &self.artists {
+            fields.push(format!("p={}", a.iter().map(|a| quote(a)).join(";")));
+        }
+        if let Some(a) = &self.album {
+            fields.push(format!("a={}", quote(a)));
+        }
+        if let Some(a) = &self.album_artist {
+            fields.push(format!("aa={}", quote(a)));
+        }
+        if let Some(t) = self.track {
+            fields.push(format!("t={t}"));
+        }
+        if let Some(d) = self.disc {
+            fields.push(format!("d={d}"));
+        }
+        if let Some(y) = self.year {
+            fields.push(format!("y={y}"));
+        }
+        if let Some(g) = &self.genres {
+            fields.push(format!("g={}", g.iter().map(|g| quote(g)).join(";")));
+        }
+
+        f.write_str(&fields.join(","))
+    }
+}
+
+impl FromStr for SongEdit {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match Self::parse_prefix(s)? {
+            (res, None) => Ok(res),
+            (_, Some(_)) => {
+                ArgError::failed_to_parse("Unexpected `:` in song edit.", s)
+                    .hint("Use `/` to quote values that contain `:`.")
+                    .err()
+            }
+        }
+    }
+}
+
+impl FromArgStr for SongEdit {}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+impl SongEdit {
+    fn set(
+        &mut self,
+        s: &str,
+        key: &str,
+        mut items: Vec<String>,
+    ) -> pareg::Result<()> {
+        let single = |items: Vec<String>| items.join(";");
+        let num = |items: Vec<String>| -> pareg::Result<u32> {
+            let v = single(items);
+            if v.is_empty() {
+                return Ok(0);
+            }
+            v.parse().map_err(|_| {
+                ArgError::failed_to_parse(format!("Invalid number `{v}`."), s)
+            })
+        };
+
+        match key.trim() {
+            "n" | "tit" | "title" | "name" => self.title = Some(single(items)),
+            "p" | "art" | "artist" | "artists" | "performer" | "auth"
+            | "author" => {
+                items.retain(|a| !a.is_empty());
+                self.artists = Some(items);
+            }
+            "a" | "alb" | "album" => self.album = Some(single(items)),
+            "aa" | "ap" | "album-artist" => {
+                self.album_artist = Some(single(items))
+            }
+            "t" | "trk" | "track" | "track-number" => {
+                self.track = Some(num(items)?)
+            }
+            "d" | "disc" => self.disc = Some(num(items)?),
+            "y" | "year" => {
+                let v = single(items);
+                self.year = Some(if v.is_empty() {
+                    0
+                } else {
+                    v.parse().map_err(|_| {
+                        ArgError::failed_to_parse(
+                            format!("Invalid year `{v}`."),
+                            s,
+                        )
+                    })?
+                });
+            }
+            "g" | "genre" | "genres" => {
+                items.retain(|g| !g.is_empty());
+                self.genres = Some(items);
+            }
+            "" => {}
+            k => {
+                return ArgError::failed_to_parse(
+                    format!("Unknown song field `{k}`."),
+                    s,
+                )
+                .hint(
+                    "Valid fields are `n`, `p`, `a`, `aa`, `t`, `d`, `y` and \
+                    `g`.",
+                )
+                .err();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Quotes the value if it contains special characters.
+fn quote(s: &str) -> String {
+    if s.contains([',', ':', ';', '/', '=']) {
+        format!("/{}/", s.replace('/', "//"))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Replaces all values of the key with the given values.
+fn set_multiple(tag: &mut Tag, key: ItemKey, values: &[String]) {
+    tag.remove_key(key);
+    for v in values {
+        tag.push(TagItem::new(key, ItemValue::Text(v.clone())));
+    }
+}
@@ -0,0 +1,30 @@
+use std::{path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Part of audio file that is played as song. Songs from cue sheets are
+/// parts of larger audio files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SongRange {
+    /// Path to the cue sheet that defines the song.
+    pub sheet: PathBuf,
+    /// Number of the track in the cue sheet.
+    pub track: u32,
+    /// Start of the song in the audio file.
+    pub start: Duration,
+    /// End of the song in the audio file. [`None`] means the end of the
+    /// file.
+    #[serde(default)]
+    pub end: Option<Duration>,
+    /// Modification time of the cue sheet as unix timestamp in seconds when
+    /// the song was loaded.
+    #[serde(default)]
+    pub(super) modified: Option<u64>,
+    /// Size of the cue sheet in bytes when the song was loaded.
+    #[serde(default)]
+    pub(super) file_size: Option<u64>,
+}
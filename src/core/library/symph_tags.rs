@@ -0,0 +1,117 @@
+use std::{fs::File, path::Path};
+
+use symphonia::{
+    core::{
+        io::{MediaSourceStream, MediaSourceStreamOptions},
+        meta::{MetadataRevision, StandardTag, Tag},
+    },
+    default::get_probe,
+};
+
+use crate::core::{Error, Result};
+
+use super::Chapter;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Reads the tags of the given file with symphonia and calls `f` for each
+/// standard tag. This is used for tags that are not read by ratag.
+///
+/// # Errors
+/// - Failed to open the file.
+/// - Symphonia doesn't support the file.
+pub(super) fn read_std_tags(
+    p: impl AsRef<Path>,
+    mut f: impl FnMut(&StandardTag),
+) -> Result<()> {
+    read_tags(p, |t| {
+        if let Some(t) = &t.std {
+            f(t);
+        }
+    })
+}
+
+/// Reads the tags of the given file with symphonia and calls `f` for each
+/// standard tag. Returns the chapters of the file.
+///
+/// # Errors
+/// - Failed to open the file.
+/// - Symphonia doesn't support the file.
+pub(super) fn read_std_tags_chapters(
+    p: impl AsRef<Path>,
+    mut f: impl FnMut(&StandardTag),
+) -> Result<Vec<Chapter>> {
+    read_tags_chapters(p, |t| {
+        if let Some(t) = &t.std {
+            f(t);
+        }
+    })
+}
+
+/// Reads the tags of the given file with symphonia and calls `f` for each
+/// tag. This can be used to read tags that don't have standard form.
+///
+/// # Errors
+/// - Failed to open the file.
+/// - Symphonia doesn't support the file.
+pub(super) fn read_tags(
+    p: impl AsRef<Path>,
+    f: impl FnMut(&Tag),
+) -> Result<()> {
+    read_tags_chapters(p, f).map(|_| ())
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+/// Reads the tags of the given file with symphonia and calls `f` for each
+/// tag. Returns the chapters of the file.
+fn read_tags_chapters(
+    p: impl AsRef<Path>,
+    mut f: impl FnMut(&Tag),
+) -> Result<Vec<Chapter>> {
+    let stream = MediaSourceStream::new(
+        Box::new(File::open(p)?),
+        MediaSourceStreamOptions::default(),
+    );
+    let mut format = get_probe()
+        .probe(
+            &Default::default(),
+            stream,
+            Default::default(),
+            Default::default(),
+        )
+        .map_err(|e| {
+            Error::invalid_value().msg(format!("Failed to probe file: {e}"))
+        })?;
+
+    let mut meta = format.metadata();
+    loop {
+        if let Some(rev) = meta.current() {
+            read_revision(rev, &mut f);
+        }
+        if meta.is_latest() {
+            break;
+        }
+        meta.pop();
+    }
+
+    Ok(format
+        .chapters()
+        .map(Chapter::from_symph)
+        .unwrap_or_default())
+}
+
+fn read_revision(rev: &MetadataRevision, f: &mut impl FnMut(&Tag)) {
+    let tags = rev
+        .media
+        .tags
+        .iter()
+        .chain(rev.per_track.iter().flat_map(|t| &t.metadata.tags));
+    for t in tags {
+        f(t);
+    }
+}
@@ -75,8 +75,13 @@ impl UampApp {
             .then_some(self.state.playback);
         let song = (old.cur_song != self.state.cur_song)
             .then_some(self.state.cur_song.map(|(i, _)| i));
+        let rating = old.rating != self.state.rating;
         let volume =
             (old.volume != self.state.volume).then_some(self.state.volume);
+        let repeat =
+            (old.repeat != self.state.repeat).then_some(self.state.repeat);
+        let shuffled = (old.shuffled != self.state.shuffled)
+            .then_some(self.state.shuffled);
         let can_go_next = (old.cur_song.is_some()
             != self.state.cur_song.is_some())
         .then_some(self.state.cur_song.is_some());
@@ -104,10 +109,14 @@ impl UampApp {
             playback.map(|pb| Property::PlaybackStatus(mpris::playback(pb))),
         );
         properties.extend(
-            song.is_some()
+            (song.is_some() || rating)
                 .then(|| Property::Metadata(mpris::metadata(self, false).0)),
         );
         properties.extend(volume.map(|v| Property::Volume(v as f64)));
+        properties.extend(
+            repeat.map(|r| Property::LoopStatus(mpris::loop_status(r))),
+        );
+        properties.extend(shuffled.map(Property::Shuffle));
         properties.extend(can_go_next.map(Property::CanGoNext));
 
         if let Some(c) = can_go_previous {
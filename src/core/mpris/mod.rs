@@ -11,8 +11,8 @@ use crate::{
     core::{
         ControlMsg, DataControlMsg, Msg, Result, RtAndle, UampApp,
         config::{self, CacheSize},
-        library::img_lookup::lookup_image_path_rt_thread,
-        player::Playback,
+        library::{Song, img_lookup::lookup_image_path_rt_thread},
+        player::{Playback, RepeatMode},
     },
     ext::uri::{get_file_uri, parse_file_uri},
 };
@@ -245,16 +245,21 @@ impl PlayerInterface for Mpris {
     }
 
     async fn loop_status(&self) -> fdo::Result<LoopStatus> {
-        Err(fdo::Error::NotSupported(
-            "Uamp doesn't support looping in this way.".to_string(),
-        ))
+        self.request(|app| Ok(loop_status(app.player.playlist().repeat)))
+            .await
     }
 
     async fn set_loop_status(
         &self,
-        _loop_status: LoopStatus,
+        loop_status: LoopStatus,
     ) -> zbus::Result<()> {
-        Err(zbus::Error::Unsupported)
+        let mode = match loop_status {
+            LoopStatus::None => RepeatMode::None,
+            LoopStatus::Playlist => RepeatMode::Playlist,
+            LoopStatus::Track => RepeatMode::Track,
+        };
+        self.send_zmsg(Msg::Control(ControlMsg::SetPlaylistRepeat(mode)))
+            .await
     }
 
     async fn rate(&self) -> fdo::Result<PlaybackRate> {
@@ -266,13 +271,13 @@ impl PlayerInterface for Mpris {
     }
 
     async fn shuffle(&self) -> fdo::Result<bool> {
-        Err(fdo::Error::NotSupported(
-            "Uamp doesn't support shuffle in this way.".to_string(),
-        ))
+        self.request(|app| Ok(app.player.playlist().is_shuffled()))
+            .await
     }
 
-    async fn set_shuffle(&self, _shuffle: bool) -> zbus::Result<()> {
-        Err(zbus::Error::Unsupported)
+    async fn set_shuffle(&self, shuffle: bool) -> zbus::Result<()> {
+        self.send_zmsg(Msg::Control(ControlMsg::SetShuffle(Some(shuffle))))
+            .await
     }
 
     async fn metadata(&self) -> fdo::Result<Metadata> {
@@ -410,6 +415,14 @@ pub fn playback(pb: Playback) -> PlaybackStatus {
     }
 }
 
+pub fn loop_status(mode: RepeatMode) -> LoopStatus {
+    match mode {
+        RepeatMode::None => LoopStatus::None,
+        RepeatMode::Playlist => LoopStatus::Playlist,
+        RepeatMode::Track => LoopStatus::Track,
+    }
+}
+
 pub fn metadata(
     app: &UampApp,
     image: bool,
@@ -448,6 +461,9 @@ fn metadata_for(
     data.set_title(song.title());
     data.set_disc_number(song.disc().map(|d| d as i32));
     data.set_track_number(song.track().map(|t| t as i32));
+    data.set_user_rating(
+        song.rating().map(|r| r as f64 / Song::MAX_RATING as f64),
+    );
     data.set_art_url(
         song.get_cached_path(&app.config, CacheSize::Full)
             .map(|s| get_file_uri("", s.canonicalize().unwrap_or(s))),
@@ -0,0 +1,306 @@
+use std::{
+    any::Any,
+    f32::consts::FRAC_PI_2,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use raplay::{
+    Callback, SampleBufferMut, Source, Timestamp,
+    reexp::FromSample,
+    source::{DeviceConfig, VolumeIterator},
+};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Shared state between playing song and the song that will follow it.
+#[derive(Default)]
+pub struct Handoff {
+    /// When set, the playing song should end this long before its real end
+    /// and give its tail to the following song. The config is the preferred
+    /// config of the following song.
+    armed: Option<(Duration, Option<DeviceConfig>)>,
+    /// Tail of the song that should be faded out.
+    tail: Option<Box<dyn Source>>,
+}
+
+impl Handoff {
+    /// Make the song crossfade into the following song with the given
+    /// preferred config.
+    pub fn arm(&mut self, len: Duration, next: Option<DeviceConfig>) {
+        self.armed = Some((len, next));
+    }
+
+    /// Don't crossfade into the following song.
+    pub fn disarm(&mut self) {
+        self.armed = None;
+    }
+}
+
+/// Source that can crossfade into the following source.
+pub struct CrossfadeSource {
+    /// The song itself. It is [`None`] if it was given to the next song.
+    inner: Option<Box<dyn Source>>,
+    /// Handoff to the following song.
+    next: Arc<Mutex<Handoff>>,
+    /// Handoff from the previous song.
+    prev: Option<Arc<Mutex<Handoff>>>,
+    /// Tail of the previous song that is being faded out.
+    tail: Option<Tail>,
+    /// Config of the device.
+    info: Option<DeviceConfig>,
+    /// Buffer for reading the tail. It is `Vec<T>` where `T` is the type of
+    /// the samples.
+    mix_buf: Box<dyn Any + Send>,
+}
+
+impl CrossfadeSource {
+    /// Wraps the given source.
+    pub fn new(inner: Box<dyn Source>) -> Self {
+        Self {
+            inner: Some(inner),
+            next: Default::default(),
+            prev: None,
+            tail: None,
+            info: None,
+            mix_buf: Box::new(()),
+        }
+    }
+
+    /// Gets the handoff to the following song.
+    pub fn handoff(&self) -> Arc<Mutex<Handoff>> {
+        self.next.clone()
+    }
+
+    /// Fade out the tail of the previous song given by the handoff at the
+    /// start of this song.
+    pub fn fade_from(&mut self, prev: Arc<Mutex<Handoff>>) {
+        self.prev = Some(prev);
+    }
+}
+
+impl Source for CrossfadeSource {
+    fn set_err_callback(&mut self, err_callback: &Callback<raplay::Error>) {
+        if let Some(i) = &mut self.inner {
+            i.set_err_callback(err_callback);
+        }
+    }
+
+    fn init(&mut self, info: &DeviceConfig) -> anyhow::Result<()> {
+        self.info = Some(info.clone());
+        if let Some(t) = &mut self.tail {
+            t.src.init(info)?;
+        }
+        match &mut self.inner {
+            Some(i) => i.init(info),
+            None => Ok(()),
+        }
+    }
+
+    fn read(
+        &mut self,
+        buffer: &mut SampleBufferMut,
+    ) -> (usize, anyhow::Result<()>) {
+        if self.should_handoff() {
+            let mut next = self.next.lock().unwrap();
+            next.tail = self.inner.take();
+            next.armed = None;
+            return (0, Ok(()));
+        }
+
+        if let Some(prev) = self.prev.take() {
+            self.take_tail(prev);
+        }
+
+        let Some(inner) = &mut self.inner else {
+            return (0, Ok(()));
+        };
+        let (cnt, res) = inner.read(buffer);
+
+        if self.tail.is_none() {
+            return (cnt, res);
+        }
+
+        let cnt = match buffer {
+            SampleBufferMut::I8(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::I8(b))
+            }
+            SampleBufferMut::I16(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::I16(b))
+            }
+            SampleBufferMut::I24(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::I24(b))
+            }
+            SampleBufferMut::I32(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::I32(b))
+            }
+            SampleBufferMut::I64(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::I64(b))
+            }
+            SampleBufferMut::U8(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::U8(b))
+            }
+            SampleBufferMut::U16(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::U16(b))
+            }
+            SampleBufferMut::U32(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::U32(b))
+            }
+            SampleBufferMut::U64(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::U64(b))
+            }
+            SampleBufferMut::F32(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::F32(b))
+            }
+            SampleBufferMut::F64(d) => {
+                self.mix(d, cnt, |b| SampleBufferMut::F64(b))
+            }
+            _ => {
+                self.tail = None;
+                cnt
+            }
+        };
+
+        (cnt, res)
+    }
+
+    fn preferred_config(&mut self) -> Option<DeviceConfig> {
+        self.inner.as_mut().and_then(|i| i.preferred_config())
+    }
+
+    fn volume(&mut self, volume: VolumeIterator) -> bool {
+        if let Some(t) = &mut self.tail {
+            t.src.volume(volume);
+        }
+        self.inner.as_mut().is_some_and(|i| i.volume(volume))
+    }
+
+    fn seek(&mut self, time: Duration) -> anyhow::Result<Timestamp> {
+        self.tail = None;
+        match &mut self.inner {
+            Some(i) => i.seek(time),
+            None => Err(raplay::Error::NoSourceIsPlaying.into()),
+        }
+    }
+
+    fn seek_by(
+        &mut self,
+        time: Duration,
+        forward: bool,
+    ) -> anyhow::Result<Timestamp> {
+        self.tail = None;
+        match &mut self.inner {
+            Some(i) => i.seek_by(time, forward),
+            None => Err(raplay::Error::NoSourceIsPlaying.into()),
+        }
+    }
+
+    fn get_time(&self) -> Option<Timestamp> {
+        self.inner.as_ref().and_then(|i| i.get_time())
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+/// Tail of song that is being faded out.
+struct Tail {
+    src: Box<dyn Source>,
+    /// Current frame of the fade.
+    pos: usize,
+    /// Length of the fade in frames.
+    len: usize,
+}
+
+impl CrossfadeSource {
+    /// Checks whether the song is near its end and should be given to the
+    /// following song.
+    fn should_handoff(&self) -> bool {
+        let Some(inner) = &self.inner else {
+            return false;
+        };
+        let next = self.next.lock().unwrap();
+        let Some((len, cfg)) = &next.armed else {
+            return false;
+        };
+        // The following song would not be played immidietely after this.
+        if cfg.is_some() && *cfg != self.info {
+            return false;
+        }
+        inner
+            .get_time()
+            .is_some_and(|t| t.total.saturating_sub(t.current) <= *len)
+    }
+
+    fn take_tail(&mut self, prev: Arc<Mutex<Handoff>>) {
+        let Some(info) = &self.info else {
+            return;
+        };
+        let Some(mut src) = prev.lock().unwrap().tail.take() else {
+            return;
+        };
+        if src.init(info).is_err() {
+            return;
+        }
+        let Some(t) = src.get_time() else {
+            return;
+        };
+
+        let len = t.total.saturating_sub(t.current).as_secs_f32()
+            * info.sample_rate as f32;
+        self.tail = Some(Tail {
+            src,
+            pos: 0,
+            len: (len as usize).max(1),
+        });
+    }
+
+    /// Mixes the tail of the previous song into the data with `cnt` valid
+    /// samples. Returns the new number of valid samples.
+    fn mix<T>(
+        &mut self,
+        data: &mut [T],
+        cnt: usize,
+        wrap: fn(&mut [T]) -> SampleBufferMut<'_>,
+    ) -> usize
+    where
+        T: Copy + FromSample<f32> + Send + 'static,
+        f32: FromSample<T>,
+    {
+        let Some(tail) = &mut self.tail else {
+            return cnt;
+        };
+        let channels = self
+            .info
+            .as_ref()
+            .map(|i| i.channel_count.max(1) as usize)
+            .unwrap_or(1);
+
+        if !self.mix_buf.is::<Vec<T>>() {
+            self.mix_buf = Box::new(Vec::<T>::new());
+        }
+        let tmp = self.mix_buf.downcast_mut::<Vec<T>>().unwrap();
+        tmp.clear();
+        tmp.resize(data.len(), T::from_sample_(0.));
+        let (tcnt, res) = tail.src.read(&mut wrap(tmp));
+        let tcnt = if res.is_ok() { tcnt } else { 0 };
+
+        for (i, (d, t)) in data.iter_mut().zip(tmp.iter()).enumerate() {
+            let frame = tail.pos + i / channels;
+            let p = (frame as f32 / tail.len as f32).min(1.) * FRAC_PI_2;
+            let s = if i < cnt { f32::from_sample_(*d) } else { 0. };
+            let t = if i < tcnt { f32::from_sample_(*t) } else { 0. };
+            *d = T::from_sample_(s * p.sin() + t * p.cos());
+        }
+
+        tail.pos += data.len() / channels;
+        if tcnt < data.len() || tail.pos >= tail.len {
+            self.tail = None;
+        }
+
+        cnt.max(tcnt)
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use raplay::{
+    Callback, SampleBufferMut, Source, Timestamp,
+    reexp::FromSample,
+    source::{DeviceConfig, VolumeIterator},
+};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Source that amplifies the inner source by constant gain.
+pub struct GainSource {
+    inner: Box<dyn Source>,
+    amp: f32,
+}
+
+impl GainSource {
+    /// Creates source that multiplies the samples of `inner` by `amp`.
+    pub fn new(inner: Box<dyn Source>, amp: f32) -> Self {
+        Self { inner, amp }
+    }
+}
+
+impl Source for GainSource {
+    fn set_err_callback(&mut self, err_callback: &Callback<raplay::Error>) {
+        self.inner.set_err_callback(err_callback);
+    }
+
+    fn init(&mut self, info: &DeviceConfig) -> anyhow::Result<()> {
+        self.inner.init(info)
+    }
+
+    fn read(
+        &mut self,
+        buffer: &mut SampleBufferMut,
+    ) -> (usize, anyhow::Result<()>) {
+        let (cnt, res) = self.inner.read(buffer);
+        match buffer {
+            SampleBufferMut::I8(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::I16(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::I24(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::I32(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::I64(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::U8(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::U16(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::U32(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::U64(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::F32(d) => amplify(&mut d[..cnt], self.amp),
+            SampleBufferMut::F64(d) => amplify(&mut d[..cnt], self.amp),
+            _ => {}
+        }
+        (cnt, res)
+    }
+
+    fn preferred_config(&mut self) -> Option<DeviceConfig> {
+        self.inner.preferred_config()
+    }
+
+    fn volume(&mut self, volume: VolumeIterator) -> bool {
+        self.inner.volume(volume)
+    }
+
+    fn seek(&mut self, time: Duration) -> anyhow::Result<Timestamp> {
+        self.inner.seek(time)
+    }
+
+    fn seek_by(
+        &mut self,
+        time: Duration,
+        forward: bool,
+    ) -> anyhow::Result<Timestamp> {
+        self.inner.seek_by(time, forward)
+    }
+
+    fn get_time(&self) -> Option<Timestamp> {
+        self.inner.get_time()
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+fn amplify<T>(data: &mut [T], amp: f32)
+where
+    T: Copy + FromSample<f32>,
+    f32: FromSample<T>,
+{
+    for s in data {
+        *s = T::from_sample_(f32::from_sample_(*s) * amp);
+    }
+}
@@ -1,10 +1,15 @@
 mod add_policy;
+mod crossfade_source;
+mod gain_source;
 mod json;
 mod playback;
 mod player_msg;
 mod player_struct;
 mod playlist;
+mod range_source;
+mod repeat_mode;
 mod sink_wrapper;
+mod sleep_timer;
 
 //===========================================================================//
 //                                   Public                                  //
@@ -12,4 +17,5 @@ mod sink_wrapper;
 
 pub use self::{
     add_policy::*, playback::*, player_msg::*, player_struct::*, playlist::*,
+    repeat_mode::*, sleep_timer::*,
 };
@@ -1,11 +1,15 @@
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 
+use itertools::Itertools;
 use raplay::PrefetchState;
 
 use crate::core::{
-    Msg, UampApp,
-    library::{Library, LibraryUpdate, SongId},
-    server::{SubMsg, sub::PlaylistJump},
+    AppCtrl, LogResult, Msg, UampApp,
+    library::{Library, LibraryUpdate, MetaChange, ReplayGain, SongId},
+    server::{
+        SubMsg,
+        sub::{PlaylistJump, SongStats},
+    },
 };
 
 //===========================================================================//
@@ -34,21 +38,35 @@ impl UampApp {
             PlayerMsg::SongEnd(
                 PrefetchState::NoPrefetch | PrefetchState::PrefetchFailed,
             ) => {
-                self.player.play_next(&mut self.library, 1);
+                self.player.song_ended(&mut self.library);
+                if self.sleep_song_end() {
+                    self.sleep_pause(false);
+                }
+                self.player.play_auto_next(&mut self.library);
                 self.client_update(SubMsg::PlaylistJump(PlaylistJump::new(
                     &self.player,
                 )));
             }
             PlayerMsg::SongEnd(PrefetchState::PrefetchSuccessful) => {
-                self.player.prefetch_success();
+                let sleep = self.sleep_song_end();
+                self.player.prefetch_success(&mut self.library);
+                if sleep {
+                    self.sleep_pause(false);
+                }
                 self.client_update(SubMsg::PlaylistJump(PlaylistJump::new(
                     &self.player,
                 )));
             }
             PlayerMsg::Prefetch => {
-                self.player.prefetch(&mut self.library);
+                // The next song will be loaded paused.
+                if !self.sleep_at_song_end() {
+                    self.player.prefetch(&mut self.library);
+                }
+            }
+            PlayerMsg::HardPauseAt(i) => {
+                self.hard_pause_at = Some(i);
+                self.restore_sleep_fade();
             }
-            PlayerMsg::HardPauseAt(i) => self.hard_pause_at = Some(i),
         }
         vec![]
     }
@@ -58,6 +76,7 @@ impl UampApp {
         &mut self,
         now: Instant,
         up: LibraryUpdate,
+        meta: MetaChange,
     ) {
         // ReplaceData is handled separately in `player_id_replace`
         if up >= LibraryUpdate::RemoveData && up != LibraryUpdate::ReplaceData
@@ -66,6 +85,20 @@ impl UampApp {
                 .retain_all(&mut self.library, |l, s, _| !l[s].is_deleted());
         }
 
+        if up >= LibraryUpdate::NewData {
+            self.refresh_smart_playlists(MetaChange::all());
+        } else if !meta.is_empty() {
+            self.refresh_smart_playlists(meta);
+        }
+
+        self.player.check_played(&mut self.library);
+        for id in self.player.take_stat_updates() {
+            self.client_update(SubMsg::SongStats(SongStats::new(
+                id,
+                &self.library[id],
+            )));
+        }
+
         if let Some(t) = self.hard_pause_at
             && t <= now
         {
@@ -76,6 +109,30 @@ impl UampApp {
         }
     }
 
+    /// Determines the replay gain of loaded songs that don't have it in
+    /// background.
+    pub(in crate::core) fn replay_gain_routine(&mut self, ctrl: &mut AppCtrl) {
+        for id in self.player.take_replay_gain_requests().into_iter().unique()
+        {
+            let path = self.library[id].path().to_owned();
+            let p = path.clone();
+            let task = move || ReplayGain::determine(p);
+            ctrl.task(async move {
+                match tokio::task::spawn_blocking(task).await {
+                    Ok(rg) => Msg::fn_delegate(move |app, _| {
+                        app.finish_replay_gain(id, &path, rg);
+                        Ok(vec![])
+                    }),
+                    Err(e) => {
+                        Err::<(), _>(e)
+                            .or_log_err("Failed to determine replay gain.");
+                        Msg::None
+                    }
+                }
+            });
+        }
+    }
+
     /// Old song ids were replaced with new valid song ids.
     pub(in crate::core) fn player_id_replace(
         &mut self,
@@ -85,4 +142,62 @@ impl UampApp {
             !l[s].is_deleted() && !n(s, l)
         });
     }
+
+    /// Reevaluates the queries of smart playlists in the playlist stack that
+    /// depend on the changed metadata. [`MetaChange::all`] reevaluates all of
+    /// them.
+    pub(in crate::core) fn refresh_smart_playlists(
+        &mut self,
+        change: MetaChange,
+    ) {
+        let mut refreshed = false;
+        for (idx, name) in self.player.smart_playlists() {
+            let Some(sp) = self.config.smart_playlists().get(&name) else {
+                continue;
+            };
+            if !change.is_all()
+                && !sp
+                    .0
+                    .meta_deps(self.config.default_base())
+                    .intersects(change)
+            {
+                continue;
+            }
+            let Some(songs) =
+                sp.0.get_ids(
+                    &self.library,
+                    self.config.sort_opts(),
+                    self.config.default_base().clone(),
+                    &self.player,
+                )
+                .or_log_err_with(|| {
+                    format!("Failed to refresh smart playlist `{name}`.")
+                })
+            else {
+                continue;
+            };
+            self.player.replace_songs(idx, songs.into());
+            refreshed = true;
+        }
+
+        if refreshed {
+            self.client_update_set_all();
+        }
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+impl UampApp {
+    /// Stores the replay gain determined in background. The song may have
+    /// changed in the meantime, so its path is checked.
+    fn finish_replay_gain(&mut self, id: SongId, path: &Path, rg: ReplayGain) {
+        let song = &mut self.library[id];
+        if song.path() == path && song.replay_gain().is_none() {
+            song.set_replay_gain(rg);
+            self.library.update_meta(MetaChange::PLAYBACK);
+        }
+    }
 }
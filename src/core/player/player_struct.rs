@@ -1,4 +1,4 @@
-use std::{cell::Cell, mem, ops::Range, time::Duration};
+use std::{cell::Cell, mem, ops::Range, sync::Arc, time::Duration};
 
 use bitflags::bitflags;
 use itertools::Itertools;
@@ -9,8 +9,8 @@ use uamp_proc::TrackChange;
 use crate::{
     core::{
         Alias, DataControlMsg, Error, LogResult, Msg, Result, RtAndle,
-        config::Config,
-        library::{Library, SongId},
+        config::{Config, ReplayGainMode, default},
+        library::{Library, MetaChange, Song, SongId},
         plugin::DecoderPlugin,
         server::sub,
     },
@@ -18,7 +18,7 @@ use crate::{
 };
 
 use super::{
-    AddPolicy, PlayerMsg, Playlist, playback::Playback,
+    AddPolicy, PlayerMsg, Playlist, RepeatMode, playback::Playback,
     sink_wrapper::SinkWrapper,
 };
 
@@ -43,6 +43,18 @@ pub struct Player {
     inner: SinkWrapper,
     flags: PlayerFlags,
 
+    /// The song that is loaded in the sink. Used for play statistics.
+    loaded: Option<SongId>,
+    /// Portion of the song that must be played so that it counts as played.
+    play_threshold: f32,
+    /// Songs at least this long remember the position where they were left.
+    resume_length: Option<Duration>,
+    /// Songs with any of these tags remember the position where they were
+    /// left.
+    resume_tags: Vec<Arc<str>>,
+    /// Songs with changed play statistics.
+    stat_updates: Vec<SongId>,
+
     #[tracker(Cell::set)]
     change: Cell<bool>,
 }
@@ -137,6 +149,38 @@ impl Player {
         self.try_load_state(lib, id, n == 1);
     }
 
+    /// Plays the song that should play after the current song ends. This
+    /// respects the repeat mode of the playlist.
+    pub fn play_auto_next(&mut self, lib: &mut Library) {
+        let id = self.mut_playlist().auto_next();
+        if id.is_none() {
+            self.flags |= PlayerFlags::PLAYLIST_END;
+        }
+        self.try_load_state(lib, id, true);
+    }
+
+    /// Sets the repeat mode of the current playlist.
+    pub fn set_repeat(&mut self, mode: RepeatMode) {
+        let pl = self.mut_playlist();
+        let next = pl.peek_auto();
+        pl.repeat = mode;
+        if pl.peek_auto() != next {
+            self.inner.unprefetch();
+            self.inner.do_prefetch_notify(true);
+        }
+    }
+
+    /// Enables or disables the shuffle mode of the current playlist.
+    pub fn set_shuffle(&mut self, shuffle: bool, shuffle_current: bool) {
+        let pl = self.mut_playlist();
+        let next = pl.peek_auto();
+        pl.set_shuffle(shuffle, shuffle_current);
+        if pl.peek_auto() != next {
+            self.inner.unprefetch();
+            self.inner.do_prefetch_notify(true);
+        }
+    }
+
     /// Plays the `n`th previous song in the playlist
     pub fn play_prev(&mut self, lib: &mut Library, n: usize) {
         let id = self.mut_playlist().nth_prev(n);
@@ -154,6 +198,21 @@ impl Player {
         self.try_load_state(lib, id, pf);
     }
 
+    /// Checks whether the song that will play after the loaded song is from
+    /// the same album.
+    pub fn next_in_album(&self, lib: &Library) -> bool {
+        match (self.loaded, self.playlist().peek_auto()) {
+            (Some(l), Some(n)) => same_album(lib, l, n),
+            _ => false,
+        }
+    }
+
+    /// Removes the prefetched song so that the next song is prefetched again.
+    pub fn reprefetch(&mut self) {
+        self.inner.unprefetch();
+        self.inner.do_prefetch_notify(true);
+    }
+
     /// Changes the state to [`Playback::Stopped`]
     pub fn stop(&mut self) {
         if !self.state.is_stopped() {
@@ -245,10 +304,100 @@ impl Player {
         self.inner.set_gapless(enable);
     }
 
+    /// Sets the crossfade length and whether songs from the same album are
+    /// crossfaded.
+    pub fn crossfade(&mut self, len: Duration, albums: bool) {
+        self.inner.set_crossfade(len, albums);
+    }
+
+    /// Sets the replay gain mode and preamp in dB. This applies from the next
+    /// loaded song.
+    pub fn replay_gain(&mut self, mode: ReplayGainMode, preamp: f32) {
+        self.inner.set_replay_gain(mode, preamp);
+    }
+
+    /// Sets the portion of song that must be played so that it is counted as
+    /// played. The value is clamped to the range from 0 to 1.
+    pub fn set_play_threshold(&mut self, threshold: f32) {
+        self.play_threshold = threshold.clamp(0., 1.);
+    }
+
+    /// Sets which songs remember the position where they were left. These are
+    /// songs at least `length` long or songs with any of the `tags`.
+    pub fn set_resume(
+        &mut self,
+        length: Option<Duration>,
+        tags: Vec<Arc<str>>,
+    ) {
+        self.resume_length = length;
+        self.resume_tags = tags;
+    }
+
+    /// Checks whether the song should remember the position where it was
+    /// left.
+    pub fn is_resumable(&self, song: &Song) -> bool {
+        self.resume_length
+            .is_some_and(|l| song.length().is_some_and(|s| s >= l))
+            || self.resume_tags.iter().any(|t| song.tags().contains(t))
+    }
+
+    /// Saves the current position of the loaded song so that it resumes from
+    /// there the next time it is loaded.
+    pub fn save_resume_pos(&mut self, lib: &mut Library) {
+        let Some(id) = self.loaded else {
+            return;
+        };
+        if self.flags.contains(PlayerFlags::ENDED)
+            || !self.is_resumable(&lib[id])
+        {
+            return;
+        }
+        let Some(t) = self.timestamp() else {
+            return;
+        };
+
+        let pos = Some(t.current).filter(|p| !p.is_zero());
+        if lib[id].resume_pos() != pos {
+            lib[id].set_resume_pos(pos);
+            lib.update_meta(MetaChange::PLAYBACK);
+            self.stat_updates.push(id);
+        }
+    }
+
     /// Configures the player
     pub fn load_config(&mut self, conf: &Config) {
         self.fade_play_pause(conf.fade_play_pause().0);
         self.inner.set_gapless(conf.gapless());
+        self.inner
+            .set_crossfade(conf.crossfade().0, conf.crossfade_albums());
+        self.inner
+            .set_replay_gain(conf.replay_gain(), conf.replay_gain_preamp());
+        self.set_play_threshold(conf.play_threshold());
+        self.set_resume(
+            conf.resume_length().map(|l| l.0),
+            conf.resume_tags().clone(),
+        );
+    }
+
+    /// Counts the loaded song as played if it has passed the play threshold.
+    pub fn check_played(&mut self, lib: &mut Library) {
+        if self.flags.contains(PlayerFlags::COUNTED) || !self.is_playing() {
+            return;
+        }
+
+        if self.timestamp().is_some_and(|t| self.passed_threshold(t)) {
+            self.count_play(lib);
+        }
+    }
+
+    /// Gets the loaded songs with unknown replay gain and clears them.
+    pub fn take_replay_gain_requests(&mut self) -> Vec<SongId> {
+        self.inner.take_replay_gain_requests()
+    }
+
+    /// Gets the songs with changed play statistics and clears them.
+    pub fn take_stat_updates(&mut self) -> Vec<SongId> {
+        mem::take(&mut self.stat_updates)
     }
 
     /// Gets timestamp of the current playback, returns [`None`] if nothing
@@ -338,10 +487,10 @@ impl Player {
         let pl = self.mut_playlist();
 
         let cur = pl.current();
-        let next = pl.peek();
+        let next = pl.peek_auto();
         pl.retain(|s, i| f(lib, s, i));
         let new_cur = pl.current();
-        let new_next = pl.peek();
+        let new_next = pl.peek_auto();
 
         if new_cur == cur {
             if new_next != next {
@@ -481,6 +630,35 @@ impl Player {
         Some(&mut self.mut_playlist_stack()[len - idx])
     }
 
+    /// Gets the indexes of smart playlists in the stack and their names.
+    pub fn smart_playlists(&self) -> Vec<(usize, String)> {
+        let len = self.playlist_stack().len();
+        let mut res: Vec<_> = self
+            .playlist_stack()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| p.smart.clone().map(|s| (len - i, s)))
+            .collect();
+        if let Some(s) = &self.playlist().smart {
+            res.push((0, s.clone()));
+        }
+        res
+    }
+
+    /// Replaces the songs in the playlist at the given index. The current
+    /// song of the playlist doesn't change.
+    pub fn replace_songs(&mut self, idx: usize, songs: Alc<Vec<SongId>>) {
+        let Some(pl) = self.get_playlist_mut(idx) else {
+            return;
+        };
+        let next = pl.peek_auto();
+        pl.replace_songs(songs);
+        if idx == 0 && self.playlist().peek_auto() != next {
+            self.inner.unprefetch();
+            self.inner.do_prefetch_notify(true);
+        }
+    }
+
     /// Removes songs with indexes in the given ranges. If the currently
     /// playing song changes, returns true.
     ///
@@ -532,6 +710,11 @@ impl Player {
             inner: SinkWrapper::new(),
             change: Cell::new(true),
             flags: PlayerFlags::NONE,
+            loaded: None,
+            play_threshold: default::play_threshold(),
+            resume_length: default::resume_length().map(|l| l.0),
+            resume_tags: vec![],
+            stat_updates: vec![],
         };
 
         res.init_inner(rt);
@@ -557,6 +740,11 @@ impl Player {
             mute,
             change: change.into(),
             flags: PlayerFlags::NONE,
+            loaded: None,
+            play_threshold: default::play_threshold(),
+            resume_length: default::resume_length().map(|l| l.0),
+            resume_tags: vec![],
+            stat_updates: vec![],
         }
     }
 
@@ -580,19 +768,45 @@ impl Player {
 
     /// Move to the next song without loading it because it was successfully
     /// prefetched.
-    pub(super) fn prefetch_success(&mut self) {
-        self.mut_playlist().nth_next(1);
+    pub(super) fn prefetch_success(&mut self, lib: &mut Library) {
+        self.song_ended(lib);
+        self.inner.prefetch_success();
+        self.flags.remove(PlayerFlags::COUNTED | PlayerFlags::ENDED);
+        self.loaded = self.mut_playlist().auto_next();
+        self.resume(lib);
+    }
+
+    /// The loaded song has played to its end.
+    pub(super) fn song_ended(&mut self, lib: &mut Library) {
+        if !self.flags.contains(PlayerFlags::COUNTED) {
+            self.count_play(lib);
+        }
+
+        self.flags |= PlayerFlags::ENDED;
+        if let Some(id) = self.loaded
+            && lib[id].resume_pos().is_some()
+        {
+            lib[id].set_resume_pos(None);
+            lib.update_meta(MetaChange::PLAYBACK);
+            self.stat_updates.push(id);
+        }
     }
 
     /// Prefetch the next song if available.
     pub(super) fn prefetch(&mut self, lib: &mut Library) {
-        let Some(id) = self.playlist().peek() else {
+        let Some(id) = self.playlist().peek_auto() else {
             return;
         };
 
-        self.inner.prefetch(lib, id).or_log_err_with(|| {
-            format!("Failed to prefetch song `{}`", lib[id].path().display())
-        });
+        let same_album = self.loaded.is_some_and(|l| same_album(lib, l, id));
+        self.inner
+            .prefetch(lib, id, same_album)
+            .or_log_err_with(|| {
+                format!(
+                    "Failed to prefetch song `{}`",
+                    lib[id].path().display()
+                )
+            });
     }
 }
 
@@ -605,6 +819,10 @@ bitflags! {
     struct PlayerFlags: u32 {
         const NONE = 0x0;
         const PLAYLIST_END = 0x1;
+        /// The loaded song has already been counted as played.
+        const COUNTED = 0x2;
+        /// The loaded song has played to its end.
+        const ENDED = 0x4;
     }
 }
 
@@ -659,6 +877,8 @@ impl Player {
         play: bool,
         pf: bool,
     ) -> bool {
+        self.finish_song(lib);
+
         let res = if pf {
             self.inner.load_or_prefetched(lib, id, play)
         } else {
@@ -672,6 +892,8 @@ impl Player {
         match res {
             Some(_) => {
                 self.state = Playback::play(play);
+                self.loaded = Some(id);
+                self.resume(lib);
                 true
             }
             None => {
@@ -685,6 +907,57 @@ impl Player {
         }
     }
 
+    /// Updates the play statistics and the resume position of the loaded song
+    /// before it is unloaded.
+    fn finish_song(&mut self, lib: &mut Library) {
+        self.save_resume_pos(lib);
+        if !self.flags.contains(PlayerFlags::COUNTED)
+            && !self.state.is_stopped()
+            && let Some(id) = self.loaded
+        {
+            if self.timestamp().is_some_and(|t| self.passed_threshold(t)) {
+                self.count_play(lib);
+            } else {
+                lib[id].mark_skipped();
+                lib.update_meta(MetaChange::STATS);
+                self.stat_updates.push(id);
+            }
+        }
+
+        self.loaded = None;
+        self.flags.remove(PlayerFlags::COUNTED | PlayerFlags::ENDED);
+    }
+
+    /// Seeks to the saved position of the loaded song if it should resume.
+    fn resume(&mut self, lib: &Library) {
+        if let Some(id) = self.loaded
+            && self.is_resumable(&lib[id])
+            && let Some(pos) = lib[id].resume_pos()
+        {
+            self.seek_to(pos).or_log_err_with(|| {
+                format!("Failed to resume `{}`.", lib[id].path().display())
+            });
+        }
+    }
+
+    fn count_play(&mut self, lib: &mut Library) {
+        let Some(id) = self.loaded else {
+            return;
+        };
+
+        self.flags |= PlayerFlags::COUNTED;
+        lib[id].mark_played();
+        lib.update_meta(MetaChange::STATS);
+        self.stat_updates.push(id);
+    }
+
+    /// Songs with unknown length are counted only when they end.
+    fn passed_threshold(&self, t: Timestamp) -> bool {
+        !t.total.is_zero()
+            && t.current.as_secs_f32()
+                >= t.total.as_secs_f32() * self.play_threshold
+    }
+
     fn inner_callback_handler(msg: CallbackInfo, rt: &RtAndle) {
         let message = match msg {
             CallbackInfo::SourceEnded(s) => Msg::Player(PlayerMsg::SongEnd(s)),
@@ -699,6 +972,13 @@ impl Player {
     }
 }
 
+/// Checks whether the two songs are from the same album.
+fn same_album(lib: &Library, a: SongId, b: SongId) -> bool {
+    lib[a].album().is_some()
+        && lib[a].album() == lib[b].album()
+        && lib[a].album_artist() == lib[b].album_artist()
+}
+
 /// returns the default volume
 pub(super) fn default_volume() -> f32 {
     1.
@@ -1,4 +1,6 @@
-use std::{ops::Index, slice::SliceIndex, time::Duration};
+use std::{
+    collections::HashMap, ops::Index, slice::SliceIndex, time::Duration,
+};
 
 use log::info;
 use rand::{rng, seq::SliceRandom};
@@ -8,12 +10,12 @@ use crate::{
     core::{
         Alias,
         library::{Library, SongId},
-        query::SongOrder,
+        query::{SongOrder, SortOpts},
     },
     ext::Alc,
 };
 
-use super::AddPolicy;
+use super::{AddPolicy, RepeatMode};
 
 //===========================================================================//
 //                                   Public                                  //
@@ -34,6 +36,15 @@ pub struct Playlist {
     /// How songs should be added to the playlist.
     #[serde(default)]
     pub add_policy: AddPolicy,
+    /// Name of the smart playlist from which this playlist was created.
+    #[serde(default)]
+    pub smart: Option<String>,
+    /// What to do when song ends.
+    #[serde(default)]
+    pub repeat: RepeatMode,
+    /// Original order of the songs if the playlist is shuffled.
+    #[serde(default)]
+    unshuffled: Option<Alc<Vec<SongId>>>,
 }
 
 impl Playlist {
@@ -55,6 +66,9 @@ impl Playlist {
             play_pos: None,
             on_end: None,
             add_policy: AddPolicy::None,
+            smart: None,
+            repeat: RepeatMode::None,
+            unshuffled: None,
         }
     }
 
@@ -87,6 +101,30 @@ impl Playlist {
         }
     }
 
+    /// Checks whether the playlist is in shuffle mode.
+    pub fn is_shuffled(&self) -> bool {
+        self.unshuffled.is_some()
+    }
+
+    /// Enables or disables the shuffle mode. When enabled, the playlist is
+    /// shuffled and the original order is remembered. When disabled, the
+    /// original order is restored. The current song stays current.
+    ///
+    /// - `shuffle_current`: when `false`, the current song will be moved to
+    ///   the first position.
+    pub fn set_shuffle(&mut self, shuffle: bool, shuffle_current: bool) {
+        if shuffle == self.is_shuffled() {
+            return;
+        }
+
+        if shuffle {
+            self.unshuffled = Some(Alc::clone(&mut self.songs));
+            self.shuffle(shuffle_current);
+        } else {
+            self.unshuffle();
+        }
+    }
+
     /// Retain only songs that match the predicate.
     pub fn retain(&mut self, mut f: impl FnMut(SongId, usize) -> bool) {
         let cur = self.current;
@@ -111,6 +149,35 @@ impl Playlist {
         }
     }
 
+    /// Replaces the songs in the playlist. The current song stays current. If
+    /// it is not in the new songs, it is inserted at its position.
+    ///
+    /// If the playlist is shuffled, the new songs are kept in the shuffled
+    /// order and songs that are new are mixed in after the current song.
+    pub fn replace_songs(&mut self, songs: Alc<Vec<SongId>>) {
+        let cur = self.current();
+        let new = if let Some(orig) = &mut self.unshuffled {
+            let (order, new) = order_like(&self.songs, &songs);
+            *orig = songs;
+            self.songs = order.into();
+            new
+        } else {
+            self.songs = songs;
+            vec![]
+        };
+
+        if let Some(cur) = cur {
+            if let Some(p) = self.songs.iter().position(|s| *s == cur) {
+                self.current = p;
+            } else {
+                self.current = self.current.min(self.songs.len());
+                self.songs.insert(self.current, cur);
+            }
+        }
+
+        self.songs.mix_after(self.current, new);
+    }
+
     /// Add songs to the playlist.
     ///
     /// - `songs`: Iterator over songs to add.
@@ -131,8 +198,11 @@ impl Playlist {
     }
 
     /// Sorts the songs according to the song order.
-    pub fn sort(&mut self, lib: &Library, simple: bool, order: SongOrder) {
-        order.sort(lib, &mut self.songs[..], simple, Some(&mut self.current))
+    ///
+    /// This disables the shuffle mode without restoring the original order.
+    pub fn sort(&mut self, lib: &Library, opts: SortOpts, order: SongOrder) {
+        self.unshuffled = None;
+        order.sort(lib, &mut self.songs[..], opts, Some(&mut self.current))
     }
 
     /// Gets the length of the playlist.
@@ -177,6 +247,18 @@ impl Playlist {
         }
     }
 
+    /// Get the song that will play when the current song ends. This respects
+    /// the repeat mode.
+    pub fn peek_auto(&self) -> Option<SongId> {
+        match self.repeat {
+            RepeatMode::None => self.peek(),
+            RepeatMode::Playlist => {
+                self.peek().or_else(|| self.songs.first().copied())
+            }
+            RepeatMode::Track => self.current(),
+        }
+    }
+
     pub fn insert(&mut self, index: usize, songs: &[SongId]) {
         self.songs.splice(index..index, songs.iter().copied());
         if index <= self.current {
@@ -201,6 +283,25 @@ impl Playlist {
         }
     }
 
+    /// Moves current to the song that should play after the current song ends
+    /// and returns its id. This respects the repeat mode.
+    ///
+    /// # Returns
+    /// [`None`] if the playlist has ended.
+    pub(super) fn auto_next(&mut self) -> Option<SongId> {
+        match self.repeat {
+            RepeatMode::None => self.nth_next(1),
+            RepeatMode::Playlist => {
+                self.current += 1;
+                if self.current >= self.len() {
+                    self.current = 0;
+                }
+                self.current()
+            }
+            RepeatMode::Track => self.current(),
+        }
+    }
+
     /// Moves current to the nth previous song and returns its id.
     ///
     /// If the position is outside of the playlist, move to the first song in
@@ -315,4 +416,43 @@ impl Playlist {
             info!("Failed to locate current song. Playlist is empty.");
         }
     }
+
+    fn unshuffle(&mut self) {
+        let Some(orig) = self.unshuffled.take() else {
+            return;
+        };
+
+        let cur = self.current();
+        let (mut order, new) = order_like(&orig, &self.songs);
+        // Songs that were added while shuffled are kept at the end.
+        order.extend(new);
+        self.songs = order.into();
+        if let Some(cur) = cur {
+            self.locate_current(cur);
+        }
+    }
+}
+
+/// Orders `songs` by their order in `order`. Returns the ordered songs and the
+/// songs that are not in `order` in their original order.
+fn order_like(
+    order: &[SongId],
+    songs: &[SongId],
+) -> (Vec<SongId>, Vec<SongId>) {
+    let mut cnt: HashMap<SongId, usize> = HashMap::new();
+    for s in songs {
+        *cnt.entry(*s).or_default() += 1;
+    }
+
+    let mut take = |s: &SongId| match cnt.get_mut(s) {
+        Some(c) if *c > 0 => {
+            *c -= 1;
+            true
+        }
+        _ => false,
+    };
+
+    let ordered = order.iter().copied().filter(|s| take(s)).collect();
+    let rest = songs.iter().copied().filter(|s| take(s)).collect();
+    (ordered, rest)
 }
@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use raplay::{
+    Callback, SampleBufferMut, Source, Timestamp,
+    source::{DeviceConfig, VolumeIterator},
+};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Source that plays only part of the inner source. Time of the source is
+/// relative to the start of the part.
+pub struct RangeSource {
+    inner: Box<dyn Source>,
+    /// Start of the part in the inner source.
+    start: Duration,
+    /// End of the part in the inner source. [`None`] means the end of the
+    /// inner source.
+    end: Option<Duration>,
+    /// Config of the device.
+    info: Option<DeviceConfig>,
+    /// Position in the inner source when `frames` started counting.
+    base: Duration,
+    /// Number of frames read since `base`.
+    frames: u64,
+    /// How much should be skipped before playing. Seeking may not be exact.
+    skip: Duration,
+}
+
+impl RangeSource {
+    /// Creates source that plays `inner` from `start` to `end`.
+    ///
+    /// # Errors
+    /// - Failed to seek to the start.
+    pub fn new(
+        inner: Box<dyn Source>,
+        start: Duration,
+        end: Option<Duration>,
+    ) -> anyhow::Result<Self> {
+        let mut res = Self {
+            inner,
+            start,
+            end,
+            info: None,
+            base: Duration::ZERO,
+            frames: 0,
+            skip: Duration::ZERO,
+        };
+        if !start.is_zero() {
+            res.seek(Duration::ZERO)?;
+        }
+        Ok(res)
+    }
+}
+
+impl Source for RangeSource {
+    fn set_err_callback(&mut self, err_callback: &Callback<raplay::Error>) {
+        self.inner.set_err_callback(err_callback);
+    }
+
+    fn init(&mut self, info: &DeviceConfig) -> anyhow::Result<()> {
+        // Frames with the old sample rate must not be counted with the new.
+        self.base = self.position();
+        self.frames = 0;
+        self.info = Some(info.clone());
+        self.inner.init(info)
+    }
+
+    fn read(
+        &mut self,
+        buffer: &mut SampleBufferMut,
+    ) -> (usize, anyhow::Result<()>) {
+        let Some(info) = &self.info else {
+            return self.inner.read(buffer);
+        };
+        let channels = info.channel_count.max(1) as usize;
+        let rate = info.sample_rate;
+
+        while !self.skip.is_zero() {
+            let len =
+                (to_frames(self.skip, rate) * channels).min(buffer.len());
+            let Some(mut buf) = slice(buffer, len).filter(|_| len != 0) else {
+                self.skip = Duration::ZERO;
+                break;
+            };
+            let (cnt, res) = self.inner.read(&mut buf);
+            self.frames += (cnt / channels) as u64;
+            if res.is_err() || cnt < len {
+                return (0, res);
+            }
+            self.skip = self
+                .skip
+                .saturating_sub(to_duration((cnt / channels) as u64, rate));
+        }
+
+        let len = match self.end {
+            Some(e) => {
+                let left = e.saturating_sub(self.position());
+                (to_frames(left, rate) * channels).min(buffer.len())
+            }
+            None => buffer.len(),
+        };
+
+        let (cnt, res) = if len < buffer.len()
+            && let Some(mut buf) = slice(buffer, len)
+        {
+            self.inner.read(&mut buf)
+        } else {
+            self.inner.read(buffer)
+        };
+        self.frames += (cnt / channels) as u64;
+        (cnt, res)
+    }
+
+    fn preferred_config(&mut self) -> Option<DeviceConfig> {
+        self.inner.preferred_config()
+    }
+
+    fn volume(&mut self, volume: VolumeIterator) -> bool {
+        self.inner.volume(volume)
+    }
+
+    fn seek(&mut self, time: Duration) -> anyhow::Result<Timestamp> {
+        let mut target = self.start + time;
+        if let Some(e) = self.end {
+            target = target.min(e);
+        }
+
+        let ts = self.inner.seek(target)?;
+        self.base = ts.current;
+        self.frames = 0;
+        self.skip = target.saturating_sub(ts.current);
+        Ok(self.timestamp(ts.total))
+    }
+
+    fn get_time(&self) -> Option<Timestamp> {
+        self.inner.get_time().map(|t| self.timestamp(t.total))
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+impl RangeSource {
+    /// Gets the current position in the inner source.
+    fn position(&self) -> Duration {
+        match &self.info {
+            Some(i) => self.base + to_duration(self.frames, i.sample_rate),
+            None => self.base,
+        }
+    }
+
+    /// Gets the timestamp relative to the part. `total` is the total length
+    /// of the inner source.
+    fn timestamp(&self, total: Duration) -> Timestamp {
+        let end = self.end.map_or(total, |e| e.min(total));
+        let total = end.saturating_sub(self.start);
+        let current = (self.position() + self.skip)
+            .saturating_sub(self.start)
+            .min(total);
+        Timestamp::new(current, total)
+    }
+}
+
+fn to_frames(d: Duration, rate: u32) -> usize {
+    (d.as_secs_f64() * rate as f64).round() as usize
+}
+
+fn to_duration(frames: u64, rate: u32) -> Duration {
+    Duration::from_secs_f64(frames as f64 / rate.max(1) as f64)
+}
+
+/// Gets the first `len` samples of the buffer. Returns [`None`] for unknown
+/// sample formats.
+fn slice<'a>(
+    buffer: &'a mut SampleBufferMut,
+    len: usize,
+) -> Option<SampleBufferMut<'a>> {
+    Some(match buffer {
+        SampleBufferMut::I8(d) => SampleBufferMut::I8(&mut d[..len]),
+        SampleBufferMut::I16(d) => SampleBufferMut::I16(&mut d[..len]),
+        SampleBufferMut::I24(d) => SampleBufferMut::I24(&mut d[..len]),
+        SampleBufferMut::I32(d) => SampleBufferMut::I32(&mut d[..len]),
+        SampleBufferMut::I64(d) => SampleBufferMut::I64(&mut d[..len]),
+        SampleBufferMut::U8(d) => SampleBufferMut::U8(&mut d[..len]),
+        SampleBufferMut::U16(d) => SampleBufferMut::U16(&mut d[..len]),
+        SampleBufferMut::U32(d) => SampleBufferMut::U32(&mut d[..len]),
+        SampleBufferMut::U64(d) => SampleBufferMut::U64(&mut d[..len]),
+        SampleBufferMut::F32(d) => SampleBufferMut::F32(&mut d[..len]),
+        SampleBufferMut::F64(d) => SampleBufferMut::F64(&mut d[..len]),
+        _ => return None,
+    })
+}
@@ -0,0 +1,53 @@
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use pareg::{ArgError, FromArgStr};
+use serde::{Deserialize, Serialize};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Describes what happens when song ends.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+pub enum RepeatMode {
+    /// Continue to the next song and end at the end of the playlist.
+    #[default]
+    None,
+    /// Continue to the first song at the end of the playlist.
+    Playlist,
+    /// Repeat the current song.
+    Track,
+}
+
+impl Display for RepeatMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_char('-'),
+            Self::Playlist => f.write_char('p'),
+            Self::Track => f.write_char('t'),
+        }
+    }
+}
+
+impl FromStr for RepeatMode {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "-" | "none" | "off" => Ok(Self::None),
+            "p" | "pl" | "playlist" => Ok(Self::Playlist),
+            "t" | "track" | "song" => Ok(Self::Track),
+            c => ArgError::failed_to_parse(
+                format!("Unknown repeat mode `{c}`"),
+                s,
+            )
+            .hint("Valid options are: `-`, `p` or `t`.")
+            .err(),
+        }
+    }
+}
+
+impl FromArgStr for RepeatMode {}
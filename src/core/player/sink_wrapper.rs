@@ -1,4 +1,11 @@
-use std::{fmt::Debug, fs::File, path::Path, time::Duration};
+use std::{
+    fmt::Debug,
+    fs::File,
+    mem,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use raplay::{
     CallbackInfo, CpalError, Sink, Timestamp,
@@ -9,12 +16,19 @@ use ratag::{TagType, tag};
 
 use crate::core::{
     Error, LogResult, Result,
-    library::{Library, LibraryUpdate, SongId},
+    config::ReplayGainMode,
+    library::{Library, MetaChange, SongId},
     log_err,
     plugin::DecoderPlugin,
     warn,
 };
 
+use super::{
+    crossfade_source::{CrossfadeSource, Handoff},
+    gain_source::GainSource,
+    range_source::RangeSource,
+};
+
 /// Wrapps the sink
 pub struct SinkWrapper {
     /// The inner player
@@ -22,6 +36,20 @@ pub struct SinkWrapper {
     /// Configuration for symph sources
     symph: symph::Options,
     decoder_plugins: Vec<DecoderPlugin>,
+    /// Which replay gain is used.
+    replay_gain: ReplayGainMode,
+    /// Gain in dB added to the replay gain.
+    preamp: f32,
+    /// Length of crossfade between songs.
+    crossfade: Duration,
+    /// Crossfade also between consecutive songs from the same album.
+    crossfade_albums: bool,
+    /// Handoff of the playing song.
+    cur_handoff: Option<Arc<Mutex<Handoff>>>,
+    /// Handoff of the prefetched song.
+    pf_handoff: Option<Arc<Mutex<Handoff>>>,
+    /// Loaded songs with unknown replay gain.
+    rg_requests: Vec<SongId>,
 }
 
 impl SinkWrapper {
@@ -33,14 +61,20 @@ impl SinkWrapper {
         }))
         .expect("Failed to set sink error callback: ");
 
-        // XXX: Make this configurable?
-        sink.prefetch_notify(Duration::from_secs(1))
+        sink.prefetch_notify(PREFETCH_TIME)
             .expect("Failed to set prefetch time on sink.");
 
         Self {
             sink,
             symph: symph::Options::default(),
             decoder_plugins: vec![],
+            replay_gain: ReplayGainMode::Off,
+            preamp: 0.,
+            crossfade: Duration::ZERO,
+            crossfade_albums: false,
+            cur_handoff: None,
+            pf_handoff: None,
+            rg_requests: vec![],
         }
     }
 
@@ -59,19 +93,46 @@ impl SinkWrapper {
         id: SongId,
         play: bool,
     ) -> Result<()> {
-        let src = self.load_song(lib, id)?;
+        let (src, handoff) = wrap(self.load_song(lib, id)?);
         self.unprefetch();
-        self.load_inner(src, play)?;
+        self.load_inner(src, handoff, play)?;
         Ok(())
     }
 
     /// Prefetch the given song.
-    pub fn prefetch(&mut self, lib: &mut Library, id: SongId) -> Result<()> {
+    ///
+    /// - `same_album`: the song is from the same album as the playing song.
+    pub fn prefetch(
+        &mut self,
+        lib: &mut Library,
+        id: SongId,
+        same_album: bool,
+    ) -> Result<()> {
         let src = self.load_song(lib, id)?;
-        self.sink.prefetch(Some(src))?;
+        let mut src = CrossfadeSource::new(src);
+
+        if let Some(cur) = &self.cur_handoff {
+            let mut cur = cur.lock().unwrap();
+            if self.crossfade.is_zero()
+                || (same_album && !self.crossfade_albums)
+            {
+                cur.disarm();
+            } else {
+                cur.arm(self.crossfade, src.preferred_config());
+                src.fade_from(self.cur_handoff.clone().unwrap());
+            }
+        }
+
+        self.pf_handoff = Some(src.handoff());
+        self.sink.prefetch(Some(Box::new(src)))?;
         Ok(())
     }
 
+    /// The prefetched song has started playing.
+    pub fn prefetch_success(&mut self) {
+        self.cur_handoff = self.pf_handoff.take();
+    }
+
     /// true - Send prefetch notification even if it has already been sent.
     ///
     /// false - Don't sent prefetch notification for the current source.
@@ -86,23 +147,30 @@ impl SinkWrapper {
         id: SongId,
         play: bool,
     ) -> Result<()> {
+        if let Some(cur) = &self.cur_handoff {
+            cur.lock().unwrap().disarm();
+        }
+        let handoff = self.pf_handoff.take();
         let src = self
             .sink
             .prefetch(None)
             .expect("Failed to retrieve prefetched source.");
 
-        let src = if let Some(src) = src {
-            src
-        } else {
-            self.load_song(lib, id)?
+        let (src, handoff) = match (src, handoff) {
+            (Some(src), Some(handoff)) => (src, handoff),
+            _ => wrap(self.load_song(lib, id)?),
         };
 
-        self.load_inner(src, play)?;
+        self.load_inner(src, handoff, play)?;
         Ok(())
     }
 
     /// Returns true if there was prefetched song.
-    pub fn unprefetch(&self) -> bool {
+    pub fn unprefetch(&mut self) -> bool {
+        if let Some(cur) = &self.cur_handoff {
+            cur.lock().unwrap().disarm();
+        }
+        self.pf_handoff = None;
         self.sink
             .prefetch(None)
             .expect("Failed to remove prefetched song")
@@ -181,6 +249,28 @@ impl SinkWrapper {
         self.symph.decoder.gapless = v;
     }
 
+    /// Sets the crossfade length and whether songs from the same album are
+    /// crossfaded (applies from the next prefetch).
+    pub fn set_crossfade(&mut self, len: Duration, albums: bool) {
+        self.crossfade = len;
+        self.crossfade_albums = albums;
+        self.sink
+            .prefetch_notify(PREFETCH_TIME + len)
+            .expect("Failed to set prefetch time on sink.");
+    }
+
+    /// Sets the replay gain mode and preamp in dB (applies only for the
+    /// following calls to load).
+    pub fn set_replay_gain(&mut self, mode: ReplayGainMode, preamp: f32) {
+        self.replay_gain = mode;
+        self.preamp = preamp;
+    }
+
+    /// Gets the loaded songs with unknown replay gain and clears them.
+    pub fn take_replay_gain_requests(&mut self) -> Vec<SongId> {
+        mem::take(&mut self.rg_requests)
+    }
+
     /// Gets the current timestamp of the playing source.
     ///
     /// # Errors
@@ -216,22 +306,41 @@ impl SinkWrapper {
         lib: &mut Library,
         id: SongId,
     ) -> Result<Box<dyn Source>> {
-        let src = self.choose_decoder(lib[id].path())?;
+        let mut src = self.choose_decoder(lib[id].path())?;
+        if let Some(r) = lib[id].range() {
+            src = Box::new(RangeSource::new(src, r.start, r.end)?);
+        }
 
         const SMALL_TIME: Duration = Duration::from_millis(100);
         if let Some(Timestamp { total, .. }) = src.get_time() {
             if let Some(len) = lib[id].length() {
                 if len.abs_diff(total) > SMALL_TIME {
                     lib[id].set_length(total);
-                    lib.update(LibraryUpdate::Metadata);
+                    lib.update_meta(MetaChange::LENGTH);
                 }
             } else {
                 lib[id].set_length(total);
-                lib.update(LibraryUpdate::Metadata);
+                lib.update_meta(MetaChange::LENGTH);
             }
         }
 
-        Ok(src)
+        if self.replay_gain == ReplayGainMode::Off {
+            return Ok(src);
+        }
+
+        // Determining the replay gain may take long time, so the song is
+        // played without it and the replay gain is used the next time.
+        let Some(rg) = lib[id].replay_gain() else {
+            self.rg_requests.push(id);
+            return Ok(src);
+        };
+
+        let amp = rg.amplitude(self.replay_gain, self.preamp);
+        if amp == 1. {
+            Ok(src)
+        } else {
+            Ok(Box::new(GainSource::new(src, amp)))
+        }
     }
 
     fn choose_decoder(&self, p: impl AsRef<Path>) -> Result<Box<dyn Source>> {
@@ -275,7 +384,14 @@ impl SinkWrapper {
         unreachable!();
     }
 
-    fn load_inner(&mut self, src: Box<dyn Source>, play: bool) -> Result<()> {
+    fn load_inner(
+        &mut self,
+        src: Box<dyn Source>,
+        handoff: Arc<Mutex<Handoff>>,
+        play: bool,
+    ) -> Result<()> {
+        self.cur_handoff = Some(handoff);
+        self.pf_handoff = None;
         let mut src = Some(src);
         match self.sink.try_load(&mut src, play) {
             e @ Err(raplay::Error::Cpal(CpalError::BuildStream(
@@ -303,6 +419,16 @@ impl Debug for SinkWrapper {
     }
 }
 
+/// How long before the end of song is the next song prefetched.
+const PREFETCH_TIME: Duration = Duration::from_secs(1);
+
+/// Wraps the source so that it can crossfade.
+fn wrap(src: Box<dyn Source>) -> (Box<dyn Source>, Arc<Mutex<Handoff>>) {
+    let src = CrossfadeSource::new(src);
+    let handoff = src.handoff();
+    (Box::new(src), handoff)
+}
+
 fn symphonia_unsupported(typ: &TagType) -> bool {
     *typ == TagType::Asf
 }
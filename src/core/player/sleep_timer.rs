@@ -0,0 +1,181 @@
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    AppCtrl, Error, LogResult, Msg, Result, UampApp, server::SubMsg,
+};
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Condition when the sleep timer pauses the playback.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum SleepMode {
+    /// Pause after the given time.
+    Time(Duration),
+    /// Pause after the given number of songs end. `1` is the current song.
+    Songs(usize),
+    /// Pause at the end of the current album.
+    Album,
+}
+
+/// Sleep timer that pauses the playback when its condition is met.
+#[derive(Debug, Default)]
+pub struct SleepTimer {
+    /// Condition of the timer. [`None`] if the timer is not set.
+    cond: Option<SleepCond>,
+    /// Fade length of the player was changed to `sleep_fade` and it should
+    /// be restored when the fade-out ends.
+    fade: bool,
+}
+
+impl UampApp {
+    /// Sets the sleep timer. Any previous timer is replaced.
+    ///
+    /// # Errors
+    /// - The number of songs is zero.
+    pub fn set_sleep(
+        &mut self,
+        ctrl: &mut AppCtrl,
+        mode: SleepMode,
+    ) -> Result<()> {
+        let cond = match mode {
+            SleepMode::Time(t) => {
+                let deadline = Instant::now() + t;
+                ctrl.task(async move {
+                    tokio::time::sleep_until(deadline.into()).await;
+                    Msg::None
+                });
+                SleepCond::Deadline(deadline)
+            }
+            SleepMode::Songs(0) => {
+                return Error::invalid_operation()
+                    .msg("Cannot set sleep timer.")
+                    .reason("The number of songs must be at least 1.")
+                    .err();
+            }
+            SleepMode::Songs(n) => SleepCond::Songs(n),
+            SleepMode::Album => SleepCond::Album,
+        };
+
+        self.sleep.cond = Some(cond);
+        // The next song must not be prefetched if the playback pauses at the
+        // end of the current song.
+        self.player.reprefetch();
+        self.client_update_sleep();
+        Ok(())
+    }
+
+    /// Cancels the sleep timer if it is set.
+    pub fn cancel_sleep(&mut self) {
+        if self.sleep.cond.take().is_some() {
+            self.player.reprefetch();
+            self.client_update_sleep();
+        }
+    }
+
+    /// Gets the remaining time or number of songs of the sleep timer.
+    pub fn sleep_remaining(&self) -> Option<SleepMode> {
+        self.sleep.cond.map(|c| match c {
+            SleepCond::Deadline(d) => {
+                SleepMode::Time(d.saturating_duration_since(Instant::now()))
+            }
+            SleepCond::Songs(n) => SleepMode::Songs(n),
+            SleepCond::Album => SleepMode::Album,
+        })
+    }
+
+    /// Checks whether the sleep timer pauses the playback when the loaded
+    /// song ends.
+    pub(in crate::core) fn sleep_at_song_end(&self) -> bool {
+        match self.sleep.cond {
+            Some(SleepCond::Songs(n)) => n <= 1,
+            Some(SleepCond::Album) => {
+                !self.player.next_in_album(&self.library)
+            }
+            _ => false,
+        }
+    }
+
+    /// Updates the sleep timer when the loaded song ends. Returns `true` if
+    /// the playback should be paused.
+    pub(in crate::core) fn sleep_song_end(&mut self) -> bool {
+        if self.sleep_at_song_end() {
+            self.sleep.cond = None;
+            self.client_update_sleep();
+            return true;
+        }
+
+        if let Some(SleepCond::Songs(n)) = &mut self.sleep.cond {
+            *n -= 1;
+            self.client_update_sleep();
+        }
+        false
+    }
+
+    /// Pauses the playback if the time of the sleep timer has passed.
+    pub(in crate::core) fn sleep_routine(&mut self, now: Instant) {
+        // Playback was resumed during the fade-out.
+        if self.sleep.fade && self.player.is_playing() {
+            self.restore_sleep_fade();
+        }
+
+        if let Some(SleepCond::Deadline(d)) = self.sleep.cond
+            && d <= now
+        {
+            self.sleep.cond = None;
+            self.client_update_sleep();
+            self.sleep_pause(true);
+        }
+    }
+
+    /// Pauses the playback because of the sleep timer. If `fade` is `true`,
+    /// the volume fades out for `sleep_fade`.
+    pub(in crate::core) fn sleep_pause(&mut self, fade: bool) {
+        if !self.player.is_playing() {
+            return;
+        }
+
+        if fade && let Some(f) = self.config.sleep_fade() {
+            self.player.fade_play_pause(f.0);
+            self.sleep.fade = true;
+        }
+        self.player
+            .play(&mut self.library, false)
+            .or_log_err("Sleep timer failed to pause.");
+        self.client_update(SubMsg::Playback(self.player.playback_state()));
+    }
+
+    /// Restores the fade length of the player after the fade-out of the sleep
+    /// timer.
+    pub(in crate::core) fn restore_sleep_fade(&mut self) {
+        if mem::take(&mut self.sleep.fade) {
+            self.player.fade_play_pause(self.config.fade_play_pause().0);
+        }
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+#[derive(Copy, Clone, Debug)]
+enum SleepCond {
+    /// Pause when this moment passes.
+    Deadline(Instant),
+    /// Pause when this number of songs end.
+    Songs(usize),
+    /// Pause when the next song is not from the same album.
+    Album,
+}
+
+impl UampApp {
+    fn client_update_sleep(&mut self) {
+        self.client_update(SubMsg::SleepTimer(self.sleep_remaining()));
+    }
+}
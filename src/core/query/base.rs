@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::core::{
     Error, Result,
-    library::{Library, SongId},
+    library::{Library, MetaChange, SongId},
     player::Player,
 };
 
@@ -17,6 +17,7 @@ pub enum Base {
     None,
     Playlist(usize),
     Tag(Arc<str>),
+    NamedPlaylist(Arc<str>),
 }
 
 impl Base {
@@ -42,9 +43,25 @@ impl Base {
                 }
             }
             Self::Tag(s) => Box::new(lib.get_tag_songs(s).iter().copied()),
+            Self::NamedPlaylist(n) => {
+                let Some(songs) = lib.get_playlist(n) else {
+                    return Err(Error::invalid_operation().msg(format!(
+                        "There is no playlist with the name `{n}`."
+                    )));
+                };
+                Box::new(songs.iter().copied())
+            }
         };
         Ok(res)
     }
+
+    /// Gets the metadata of songs that the songs in the base depend on.
+    pub fn meta_deps(&self) -> MetaChange {
+        match self {
+            Self::Tag(_) => MetaChange::TAGS,
+            _ => MetaChange::NONE,
+        }
+    }
 }
 
 impl<'a> FromArg<'a> for Base {
@@ -55,6 +72,9 @@ impl<'a> FromArg<'a> for Base {
             "all" | "_" => Ok(Self::All),
             "none" => Ok(Self::None),
             v if starts_any!(v, "tag=") => val_arg(v, '=').map(Self::Tag),
+            v if starts_any!(v, "pl=") => {
+                val_arg(v, '=').map(Self::NamedPlaylist)
+            }
             s if s.chars().next().is_some_and(|a| a.is_numeric()) => {
                 arg.arg_into().map(Self::Playlist).map_err(|_| {
                     ArgError::invalid_value("Invalid playlist index.", arg)
@@ -63,8 +83,8 @@ impl<'a> FromArg<'a> for Base {
             }
             _ => Err(ArgError::invalid_value("Invalid query base.", arg)
                 .hint(
-                    "Expected `lib`, `tmp`, `all`, `none`, `tag=<tag>` or \
-                    playlist index.",
+                    "Expected `lib`, `tmp`, `all`, `none`, `tag=<tag>`, \
+                    `pl=<name>` or playlist index.",
                 )),
         }
     }
@@ -79,6 +99,7 @@ impl Display for Base {
             Self::None => f.write_str("none"),
             Self::Playlist(p) => write!(f, "{p}"),
             Self::Tag(t) => write!(f, "tag={t}"),
+            Self::NamedPlaylist(n) => write!(f, "pl={n}"),
         }
     }
 }
@@ -4,9 +4,9 @@ use itertools::Itertools;
 use pareg::{ArgError, FromArgStr};
 use serde::{Deserialize, Serialize};
 
-use crate::core::library::Song;
+use crate::core::library::{MetaChange, SearchIndex, Song, SongSet};
 
-use super::{Filter, parser::Parser};
+use super::{Filter, lexer::Lexer, parser::Parser};
 
 // [name~/mix/+auth:imaginedragons].
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +40,59 @@ impl ComposedFilter {
             ComposedFilter::Not(q) => !q.matches(song, buf),
         }
     }
+
+    /// Gets the relevance score of the song. Scores of all filters in
+    /// conjunction are summed and the best score is used for disjunction.
+    /// Negated filters don't contribute to the score.
+    pub fn score(&self, song: &Song, buf: &mut String) -> f32 {
+        match self {
+            ComposedFilter::Filter(f) => f.score(song, buf),
+            ComposedFilter::And(q) => {
+                q.iter().map(|q| q.score(song, buf)).sum()
+            }
+            ComposedFilter::Or(q) => {
+                q.iter().map(|q| q.score(song, buf)).fold(0., f32::max)
+            }
+            ComposedFilter::Not(_) => 0.,
+        }
+    }
+
+    /// Uses the search index to find songs that may match the filter. All
+    /// songs that match the filter are in the result, but not all songs in
+    /// the result match the filter. Returns [`None`] if the index cannot be
+    /// used and all songs have to be checked.
+    pub fn candidates(&self, idx: &SearchIndex) -> Option<SongSet> {
+        match self {
+            ComposedFilter::Filter(f) => f.candidates(idx),
+            ComposedFilter::And(q) => q
+                .iter()
+                .filter_map(|q| q.candidates(idx))
+                .reduce(|a, b| a.intersect(&b)),
+            ComposedFilter::Or(q) => {
+                let mut res: Option<SongSet> = None;
+                for q in q {
+                    let c = q.candidates(idx)?;
+                    res = Some(match res {
+                        Some(r) => r.union(c),
+                        None => c,
+                    });
+                }
+                res
+            }
+            ComposedFilter::Not(_) => None,
+        }
+    }
+
+    /// Gets the metadata of songs that the filter depends on.
+    pub fn meta_deps(&self) -> MetaChange {
+        match self {
+            ComposedFilter::Filter(f) => f.meta_deps(),
+            ComposedFilter::And(q) | ComposedFilter::Or(q) => {
+                q.iter().fold(MetaChange::NONE, |a, q| a | q.meta_deps())
+            }
+            ComposedFilter::Not(q) => q.meta_deps(),
+        }
+    }
 }
 
 impl FromStr for ComposedFilter {
@@ -62,7 +115,14 @@ impl Display for ComposedFilter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ComposedFilter::Filter(ft) => {
-                f.write_str(&ft.to_string().replace('/', "//"))
+                let s = ft.to_string();
+                // `..` (range) is not special, but single `.` is.
+                if s.replace("..", "").contains(|c| Lexer::SPECIAL.contains(c))
+                {
+                    write!(f, "/{}/", s.replace('/', "//"))
+                } else {
+                    f.write_str(&s)
+                }
             }
             ComposedFilter::And(a) => {
                 if a.is_empty() {
@@ -1,9 +1,15 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, sync::OnceLock, time::Duration};
 
 use pareg::{ArgError, ArgInto, FromArgStr};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
-use crate::{core::library::Song, ext::simpl};
+use crate::{
+    core::library::{MetaChange, SearchField, SearchIndex, Song, SongSet},
+    ext::{duration_to_string, fuzzy, simpl, str_to_duration},
+};
+
+use super::NumRange;
 
 //===========================================================================//
 //                                   Public                                  //
@@ -15,6 +21,9 @@ pub struct Filter {
     typ: FilterType,
     cmp: CmpType,
     negate: bool,
+    /// Compiled regex if `cmp` is [`CmpType::Regex`].
+    #[serde(skip)]
+    regex: OnceLock<Option<Regex>>,
 }
 
 /// Filter type.
@@ -35,14 +44,28 @@ pub enum FilterType {
     Album(Option<String>),
     /// Song album artist matches given string.
     AlbumArtist(Option<String>),
+    /// Song composer contains the given string.
+    Composer(Option<String>),
+    /// Song conductor contains the given string.
+    Conductor(Option<String>),
+    /// Song performer contains the given string.
+    Performer(Option<String>),
+    /// Song lyricist contains the given string.
+    Lyricist(Option<String>),
     /// Track number has the given value.
-    Track(Option<u32>),
+    Track(Option<NumRange<u32>>),
     /// Disc number has the given value.
-    Disc(Option<u32>),
+    Disc(Option<NumRange<u32>>),
     /// Song was released within the given year.
-    Year(Option<i32>),
+    Year(Option<NumRange<i32>>),
+    /// Song length (in whole seconds) has the given value.
+    Length(Option<NumRange<Duration>>),
+    /// Song rating has the given value.
+    Rating(Option<NumRange<u8>>),
     /// Song genre contains the given string.
     Genre(Option<String>),
+    /// Path to the song file contains the given string.
+    Path(Option<String>),
     /// Song has the tag.
     Tag(Option<String>),
 }
@@ -60,18 +83,68 @@ pub enum CmpType {
     // ~
     #[default]
     LenientContains,
+    // <
+    Less,
+    // <=
+    LessEq,
+    // >
+    Greater,
+    // >=
+    GreaterEq,
+    // %
+    Regex,
+    // ?
+    Fuzzy,
 }
 
 impl Filter {
     /// Creates new filter.
     pub fn new(mut typ: FilterType, cmp: CmpType, negate: bool) -> Self {
         typ.prepare(cmp);
-        Self { cmp, typ, negate }
+        Self {
+            cmp,
+            typ,
+            negate,
+            regex: OnceLock::new(),
+        }
     }
 
     /// Check if the given song passes the filter.
     pub fn matches(&self, song: &Song, buf: &mut String) -> bool {
-        self.typ.matches(song, self.cmp, buf) ^ self.negate
+        self.typ.matches(song, self.cmp, self.regex(), buf) ^ self.negate
+    }
+
+    /// Uses the search index to find songs that may pass the filter.
+    /// Returns [`None`] if the index cannot be used for this filter.
+    pub fn candidates(&self, idx: &SearchIndex) -> Option<SongSet> {
+        // Fuzzy matches don't have to contain the trigrams of the pattern.
+        if self.negate || !self.cmp.is_lenient() || self.cmp == CmpType::Fuzzy
+        {
+            return None;
+        }
+
+        match &self.typ {
+            FilterType::AnyName(Some(s)) => Some(
+                idx.find(SearchField::Title, s)?
+                    .union(idx.find(SearchField::Artist, s)?)
+                    .union(idx.find(SearchField::Album, s)?),
+            ),
+            FilterType::Title(Some(s)) => idx.find(SearchField::Title, s),
+            FilterType::Artist(Some(s)) => idx.find(SearchField::Artist, s),
+            FilterType::Album(Some(s)) => idx.find(SearchField::Album, s),
+            FilterType::Genre(Some(s)) => idx.find(SearchField::Genre, s),
+            _ => None,
+        }
+    }
+
+    /// Gets the relevance score of the song. Better matches have larger
+    /// score. Negated filters don't contribute to the score.
+    pub fn score(&self, song: &Song, buf: &mut String) -> f32 {
+        if self.negate {
+            0.
+        } else {
+            self.typ.score(song, self.cmp, self.regex(), buf)
+        }
     }
 
     pub fn none() -> Self {
@@ -81,23 +154,66 @@ impl Filter {
     pub fn any() -> Self {
         Self::new(FilterType::Any, CmpType::Lenient, false)
     }
+
+    /// Gets the metadata of songs that the filter depends on.
+    pub fn meta_deps(&self) -> MetaChange {
+        self.typ.meta_deps()
+    }
+
+    /// Gets the compiled regex. It is compiled on the first use.
+    fn regex(&self) -> Option<&Regex> {
+        if self.cmp != CmpType::Regex {
+            return None;
+        }
+
+        self.regex
+            .get_or_init(|| {
+                self.typ.pattern().and_then(|p| Regex::new(p).ok())
+            })
+            .as_ref()
+    }
 }
 
 impl FilterType {
+    /// Gets the metadata of songs that the filter depends on.
+    pub fn meta_deps(&self) -> MetaChange {
+        match self {
+            Self::Any | Self::None | Self::Path(_) => MetaChange::NONE,
+            Self::AnyName(_)
+            | Self::Title(_)
+            | Self::Artist(_)
+            | Self::Album(_)
+            | Self::AlbumArtist(_)
+            | Self::Composer(_)
+            | Self::Conductor(_)
+            | Self::Performer(_)
+            | Self::Lyricist(_)
+            | Self::Track(_)
+            | Self::Disc(_)
+            | Self::Year(_)
+            | Self::Genre(_) => MetaChange::INFO,
+            Self::Length(_) => MetaChange::LENGTH,
+            Self::Rating(_) => MetaChange::RATING,
+            Self::Tag(_) => MetaChange::TAGS,
+        }
+    }
+
     /// Checks if the given song passes the filter.
     ///
+    /// - `re` is the compiled pattern if `cmp` is [`CmpType::Regex`].
     /// - `buf` is temporary strorage used for comparisons.
     pub fn matches(
         &self,
         song: &Song,
         cmp: CmpType,
+        re: Option<&Regex>,
         buf: &mut String,
     ) -> bool {
         macro_rules! eqo {
             ($c:expr, $s:expr) => {
                 match ($c, $s) {
                     (None, None) => true,
-                    (Some(c), Some(s)) => cmp.matches(c, s, buf),
+                    (Some(c), Some(s)) => cmp.matches(c, s, re, buf),
                     _ => false,
                 }
             };
@@ -108,12 +224,22 @@ impl FilterType {
                 let s = $s;
                 match $c {
                     None if s.is_empty() => true,
-                    Some(c) => s.iter().any(|s| cmp.matches(c, s, buf)),
+                    Some(c) => s.iter().any(|s| cmp.matches(c, s, re, buf)),
                     _ => false,
                 }
             }};
         }
 
+        macro_rules! num {
+            ($c:expr, $s:expr) => {
+                match ($c, $s) {
+                    (None, None) => true,
+                    (Some(c), Some(s)) => c.matches(&s, cmp),
+                    _ => false,
+                }
+            };
+        }
+
         match self {
             Self::Any => true,
             Self::None => false,
@@ -126,11 +252,106 @@ impl FilterType {
             Self::Artist(s) => eqs!(s, song.artists()),
             Self::Album(s) => eqo!(s, song.album()),
             Self::AlbumArtist(s) => eqo!(s, song.album_artist()),
-            Self::Track(t) => *t == song.track(),
-            Self::Disc(d) => *d == song.disc(),
-            Self::Year(y) => *y == song.year(),
+            Self::Composer(s) => eqs!(s, song.composers()),
+            Self::Conductor(s) => eqs!(s, song.conductors()),
+            Self::Performer(s) => eqs!(s, song.performers()),
+            Self::Lyricist(s) => eqs!(s, song.lyricists()),
+            Self::Track(t) => num!(t, song.track()),
+            Self::Disc(d) => num!(d, song.disc()),
+            Self::Year(y) => num!(y, song.year()),
+            Self::Length(l) => num!(
+                l,
+                song.length().map(|l| Duration::from_secs(l.as_secs()))
+            ),
+            Self::Rating(r) => num!(r, song.rating()),
             Self::Genre(s) => eqs!(s, song.genres()),
             Self::Tag(s) => eqs!(s, song.tags()),
+            Self::Path(s) => {
+                eqo!(s, Some(song.path().to_string_lossy()))
+            }
+        }
+    }
+
+    /// Gets the relevance score of the song. The score is similarity of the
+    /// matched value (see [`CmpType::score`]) multiplied by the weight of
+    /// the field.
+    ///
+    /// - `re` is the compiled pattern if `cmp` is [`CmpType::Regex`].
+    /// - `buf` is temporary strorage used for comparisons.
+    pub fn score(
+        &self,
+        song: &Song,
+        cmp: CmpType,
+        re: Option<&Regex>,
+        buf: &mut String,
+    ) -> f32 {
+        macro_rules! sco {
+            ($c:expr, $s:expr) => {
+                match ($c, $s) {
+                    (None, None) => 1.,
+                    (Some(c), Some(s)) => cmp.score(c, s, re, buf),
+                    _ => 0.,
+                }
+            };
+        }
+
+        macro_rules! scs {
+            ($c:expr, $s:expr) => {{
+                let s = $s;
+                match $c {
+                    None if s.is_empty() => 1.,
+                    Some(c) => s
+                        .iter()
+                        .map(|s| cmp.score(c, s, re, buf))
+                        .fold(0., f32::max),
+                    _ => 0.,
+                }
+            }};
+        }
+
+        let score = match self {
+            Self::AnyName(s) => (sco!(s, song.title()) * Self::TITLE_WEIGHT)
+                .max(scs!(s, song.artists()) * Self::ARTIST_WEIGHT)
+                .max(sco!(s, song.album()) * Self::ALBUM_WEIGHT),
+            Self::Title(s) => sco!(s, song.title()),
+            Self::Artist(s) => scs!(s, song.artists()),
+            Self::Album(s) => sco!(s, song.album()),
+            Self::AlbumArtist(s) => sco!(s, song.album_artist()),
+            Self::Composer(s) => scs!(s, song.composers()),
+            Self::Conductor(s) => scs!(s, song.conductors()),
+            Self::Performer(s) => scs!(s, song.performers()),
+            Self::Lyricist(s) => scs!(s, song.lyricists()),
+            Self::Genre(s) => scs!(s, song.genres()),
+            Self::Tag(s) => scs!(s, song.tags()),
+            Self::Path(s) => sco!(s, Some(song.path().to_string_lossy())),
+            _ => {
+                if self.matches(song, cmp, re, buf) {
+                    1.
+                } else {
+                    0.
+                }
+            }
+        };
+
+        score * self.weight()
+    }
+
+    /// Gets the string pattern of the filter if it has any.
+    pub fn pattern(&self) -> Option<&str> {
+        match self {
+            Self::AnyName(s)
+            | Self::Title(s)
+            | Self::Artist(s)
+            | Self::Album(s)
+            | Self::AlbumArtist(s)
+            | Self::Composer(s)
+            | Self::Conductor(s)
+            | Self::Performer(s)
+            | Self::Lyricist(s)
+            | Self::Genre(s)
+            | Self::Tag(s)
+            | Self::Path(s) => s.as_deref(),
+            _ => None,
         }
     }
 
@@ -146,7 +367,12 @@ impl FilterType {
             Self::Artist(s) => *s = s.as_deref().map(simpl::new_str),
             Self::Album(s) => *s = s.as_deref().map(simpl::new_str),
             Self::AlbumArtist(s) => *s = s.as_deref().map(simpl::new_str),
+            Self::Composer(s) => *s = s.as_deref().map(simpl::new_str),
+            Self::Conductor(s) => *s = s.as_deref().map(simpl::new_str),
+            Self::Performer(s) => *s = s.as_deref().map(simpl::new_str),
+            Self::Lyricist(s) => *s = s.as_deref().map(simpl::new_str),
             Self::Genre(s) => *s = s.as_deref().map(simpl::new_str),
+            Self::Path(s) => *s = s.as_deref().map(simpl::new_str),
             _ => {}
         }
     }
@@ -154,7 +380,10 @@ impl FilterType {
 
 impl CmpType {
     pub fn is_lenient(&self) -> bool {
-        matches!(self, CmpType::Lenient | CmpType::LenientContains)
+        matches!(
+            self,
+            CmpType::Lenient | CmpType::LenientContains | CmpType::Fuzzy
+        )
     }
 
     pub fn is_contains(&self) -> bool {
@@ -165,12 +394,31 @@ impl CmpType {
         !self.is_lenient()
     }
 
+    /// Checks if this is one of the ordering comparisons (`<`, `<=`, `>`,
+    /// `>=`).
+    pub fn is_ordering(&self) -> bool {
+        matches!(
+            self,
+            CmpType::Less
+                | CmpType::LessEq
+                | CmpType::Greater
+                | CmpType::GreaterEq
+        )
+    }
+
+    /// Checks if `s` matches the pattern. `re` is the compiled pattern if this
+    /// is [`CmpType::Regex`].
     pub fn matches(
         &self,
         pat: impl AsRef<str>,
         s: impl AsRef<str>,
+        re: Option<&Regex>,
         buf: &mut String,
     ) -> bool {
+        if *self == CmpType::Regex {
+            return re.is_some_and(|r| r.is_match(s.as_ref()));
+        }
+
         let s = if self.is_lenient() {
             buf.clear();
             simpl::to_str(s.as_ref(), buf);
@@ -179,22 +427,53 @@ impl CmpType {
             s.as_ref()
         };
 
-        if self.is_contains() {
+        if *self == CmpType::Fuzzy {
+            fuzzy::similarity(pat.as_ref(), s).is_some()
+        } else if self.is_contains() {
             s.contains(pat.as_ref())
         } else {
             *pat.as_ref() == *s
         }
     }
+
+    /// Gets how well `s` matches the pattern in range `0..=1`. Only
+    /// [`CmpType::Fuzzy`] can give other values than `0` (no match) and `1`
+    /// (match). `re` is the compiled pattern if this is [`CmpType::Regex`].
+    pub fn score(
+        &self,
+        pat: impl AsRef<str>,
+        s: impl AsRef<str>,
+        re: Option<&Regex>,
+        buf: &mut String,
+    ) -> f32 {
+        if *self != CmpType::Fuzzy {
+            return if self.matches(pat, s, re, buf) {
+                1.
+            } else {
+                0.
+            };
+        }
+
+        buf.clear();
+        simpl::to_str(s.as_ref(), buf);
+        fuzzy::similarity(pat.as_ref(), buf).unwrap_or_default()
+    }
 }
 
 impl Display for Filter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let n = if self.negate { "^" } else { "" };
         let c = match self.cmp {
-            CmpType::Strict => '=',
-            CmpType::StrictContains => '-',
-            CmpType::Lenient => ':',
-            CmpType::LenientContains => '~',
+            CmpType::Strict => "=",
+            CmpType::StrictContains => "-",
+            CmpType::Lenient => ":",
+            CmpType::LenientContains => "~",
+            CmpType::Less => "<",
+            CmpType::LessEq => "<=",
+            CmpType::Greater => ">",
+            CmpType::GreaterEq => ">=",
+            CmpType::Regex => "%",
+            CmpType::Fuzzy => "?",
         };
 
         match &self.typ {
@@ -210,16 +489,35 @@ impl Display for Filter {
             FilterType::Album(Some(a)) => write!(f, "a{n}{c}{a}"),
             FilterType::AlbumArtist(None) => write!(f, "aa{n}{c}"),
             FilterType::AlbumArtist(Some(a)) => write!(f, "aa{n}{c}{a}"),
+            FilterType::Composer(None) => write!(f, "c{n}{c}"),
+            FilterType::Composer(Some(a)) => write!(f, "c{n}{c}{a}"),
+            FilterType::Conductor(None) => write!(f, "cond{n}{c}"),
+            FilterType::Conductor(Some(a)) => write!(f, "cond{n}{c}{a}"),
+            FilterType::Performer(None) => write!(f, "pf{n}{c}"),
+            FilterType::Performer(Some(a)) => write!(f, "pf{n}{c}{a}"),
+            FilterType::Lyricist(None) => write!(f, "wr{n}{c}"),
+            FilterType::Lyricist(Some(a)) => write!(f, "wr{n}{c}{a}"),
             FilterType::Track(None) => write!(f, "t{n}{c}"),
             FilterType::Track(Some(t)) => write!(f, "t{n}{c}{t}"),
             FilterType::Disc(None) => write!(f, "d{n}{c}"),
             FilterType::Disc(Some(d)) => write!(f, "d{n}{c}{d}"),
             FilterType::Year(None) => write!(f, "y{n}{c}"),
             FilterType::Year(Some(y)) => write!(f, "y{n}{c}{y}"),
+            FilterType::Rating(None) => write!(f, "r{n}{c}"),
+            FilterType::Rating(Some(r)) => write!(f, "r{n}{c}{r}"),
+            FilterType::Length(None) => write!(f, "len{n}{c}"),
+            FilterType::Length(Some(l)) => {
+                write!(f, "len{n}{c}")?;
+                l.fmt_with(f, |l, f| {
+                    f.write_str(&duration_to_string(*l, false))
+                })
+            }
             FilterType::Genre(None) => write!(f, "g{n}{c}"),
             FilterType::Genre(Some(g)) => write!(f, "g{n}{c}{g}"),
             FilterType::Tag(None) => write!(f, "tag{n}{c}"),
             FilterType::Tag(Some(t)) => write!(f, "tag{n}{c}{t}"),
+            FilterType::Path(None) => write!(f, "path{n}{c}"),
+            FilterType::Path(Some(p)) => write!(f, "path{n}{c}{p}"),
         }
     }
 }
@@ -228,18 +526,24 @@ impl FromStr for Filter {
     type Err = ArgError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let Some((p, mut c)) = s
-            .char_indices()
-            .find(|(_, c)| matches!(c, '=' | '+' | ':' | '~' | '^'))
-        else {
+        let Some((p, mut c)) = s.char_indices().find(|(_, c)| {
+            matches!(c, '=' | '+' | ':' | '~' | '^' | '<' | '>' | '%' | '?')
+        }) else {
             return match s {
-                "any" => Ok(Self::new(FilterType::Any, CmpType::default(), false)),
-                "none" => Ok(Self::new(FilterType::None, CmpType::default(), false)),
+                "any" => {
+                    Ok(Self::new(FilterType::Any, CmpType::default(), false))
+                }
+                "none" => {
+                    Ok(Self::new(FilterType::None, CmpType::default(), false))
+                }
                 "s" | "an" | "any-name" | "n" | "tit" | "title" | "name"
                 | "p" | "art" | "artist" | "performer" | "auth" | "author"
-                | "a" | "alb" | "album" | "t" | "trk" | "track" | "album_artist" | "aa" | "ap"
-                | "track-number" | "d" | "disc" | "y" | "year" | "g"
-                | "genre" | "tag" => ArgError::failed_to_parse(
+                | "a" | "alb" | "album" | "t" | "trk" | "track"
+                | "album_artist" | "aa" | "ap" | "track-number" | "d"
+                | "disc" | "y" | "year" | "len" | "length" | "g" | "genre"
+                | "tag" | "path" | "c" | "comp" | "composer" | "cond"
+                | "conductor" | "pf" | "perf" | "performers" | "wr"
+                | "writer" | "lyricist" | "r" | "rating" => ArgError::failed_to_parse(
                     "Missing argument for filter.",
                     s,
                 )
@@ -270,6 +574,11 @@ impl FromStr for Filter {
             skip += c.len_utf8();
         }
 
+        let or_eq = matches!(c, '<' | '>') && s[p + skip..].starts_with('=');
+        if or_eq {
+            skip += '='.len_utf8();
+        }
+
         let typ = &s[..p];
         let val = &s[p + skip..];
 
@@ -278,6 +587,12 @@ impl FromStr for Filter {
             '-' => CmpType::StrictContains,
             ':' => CmpType::Lenient,
             '~' => CmpType::LenientContains,
+            '<' if or_eq => CmpType::LessEq,
+            '<' => CmpType::Less,
+            '>' if or_eq => CmpType::GreaterEq,
+            '>' => CmpType::Greater,
+            '%' => CmpType::Regex,
+            '?' => CmpType::Fuzzy,
             _ => {
                 return ArgError::failed_to_parse(
                     "Invalid comparison operator `{c}`.",
@@ -290,7 +605,72 @@ impl FromStr for Filter {
         let em =
             |e: ArgError| e.shift_span(s.len() - val.len(), s.to_string());
 
-        match typ {
+        let res = match typ {
+            "t" | "trk" | "track" | "track-number" => Ok(Self::new(
+                FilterType::Track(check_num_cmp(
+                    val.arg_into().map_err(em)?,
+                    cmp,
+                    s,
+                    val,
+                )?),
+                cmp,
+                negate,
+            )),
+            "d" | "disc" => Ok(Self::new(
+                FilterType::Disc(check_num_cmp(
+                    val.arg_into().map_err(em)?,
+                    cmp,
+                    s,
+                    val,
+                )?),
+                cmp,
+                negate,
+            )),
+            "y" | "year" => Ok(Self::new(
+                FilterType::Year(check_num_cmp(
+                    val.arg_into().map_err(em)?,
+                    cmp,
+                    s,
+                    val,
+                )?),
+                cmp,
+                negate,
+            )),
+            "r" | "rating" => Ok(Self::new(
+                FilterType::Rating(check_num_cmp(
+                    val.arg_into().map_err(em)?,
+                    cmp,
+                    s,
+                    val,
+                )?),
+                cmp,
+                negate,
+            )),
+            "len" | "length" => {
+                let l = if val.is_empty() {
+                    None
+                } else {
+                    Some(
+                        NumRange::parse_with(val, str_to_duration)
+                            .map_err(em)?,
+                    )
+                };
+                Ok(Self::new(
+                    FilterType::Length(check_num_cmp(l, cmp, s, val)?),
+                    cmp,
+                    negate,
+                ))
+            }
+            _ if cmp.is_ordering() => ArgError::failed_to_parse(
+                format!(
+                    "Filter `{typ}` doesn't support comparison `{}`.",
+                    &s[p..s.len() - val.len()]
+                ),
+                s,
+            )
+            .spanned(p..s.len() - val.len())
+            .hint("Comparisons are supported only for numeric filters.")
+            .err(),
             "s" | "an" | "any-name" => Ok(Self::new(
                 FilterType::AnyName(val.arg_into().map_err(em)?),
                 cmp,
@@ -318,18 +698,23 @@ impl FromStr for Filter {
                 cmp,
                 negate,
             )),
-            "t" | "trk" | "track" | "track-number" => Ok(Self::new(
-                FilterType::Track(val.arg_into().map_err(em)?),
+            "c" | "comp" | "composer" => Ok(Self::new(
+                FilterType::Composer(val.arg_into().map_err(em)?),
                 cmp,
                 negate,
             )),
-            "d" | "disc" => Ok(Self::new(
-                FilterType::Disc(val.arg_into().map_err(em)?),
+            "cond" | "conductor" => Ok(Self::new(
+                FilterType::Conductor(val.arg_into().map_err(em)?),
                 cmp,
                 negate,
             )),
-            "y" | "year" => Ok(Self::new(
-                FilterType::Year(val.arg_into().map_err(em)?),
+            "pf" | "perf" | "performers" => Ok(Self::new(
+                FilterType::Performer(val.arg_into().map_err(em)?),
+                cmp,
+                negate,
+            )),
+            "wr" | "writer" | "lyricist" => Ok(Self::new(
+                FilterType::Lyricist(val.arg_into().map_err(em)?),
                 cmp,
                 negate,
             )),
@@ -343,14 +728,105 @@ impl FromStr for Filter {
                 cmp,
                 negate,
             )),
+            "path" => Ok(Self::new(
+                FilterType::Path(val.arg_into().map_err(em)?),
+                cmp,
+                negate,
+            )),
             v => ArgError::failed_to_parse(
                 format!("Unknown filter type `{v}`."),
                 s,
             )
             .spanned(0..p)
             .err(),
+        }?;
+
+        if cmp == CmpType::Regex
+            && let Some(pat) = res.typ.pattern()
+        {
+            let re = Regex::new(pat).map_err(|e| {
+                ArgError::failed_to_parse(
+                    format!("Invalid regex: {e}"),
+                    s.to_string(),
+                )
+                .spanned(s.len() - val.len()..s.len())
+            })?;
+            _ = res.regex.set(Some(re));
         }
+
+        Ok(res)
     }
 }
 
 impl FromArgStr for Filter {}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+impl FilterType {
+    const TITLE_WEIGHT: f32 = 1.;
+    const ARTIST_WEIGHT: f32 = 0.9;
+    const ALBUM_WEIGHT: f32 = 0.8;
+
+    /// Weight of the field in the relevance score. Matches in the fields
+    /// that better identify the song are more relevant.
+    fn weight(&self) -> f32 {
+        match self {
+            Self::Title(_) => Self::TITLE_WEIGHT,
+            Self::Artist(_) | Self::AlbumArtist(_) => Self::ARTIST_WEIGHT,
+            Self::Album(_) => Self::ALBUM_WEIGHT,
+            Self::Composer(_)
+            | Self::Conductor(_)
+            | Self::Performer(_)
+            | Self::Lyricist(_) => 0.7,
+            Self::Genre(_) | Self::Tag(_) | Self::Path(_) => 0.5,
+            // Weights of the fields are already included.
+            Self::AnyName(_) => 1.,
+            _ => 0.5,
+        }
+    }
+}
+
+/// Ordering comparisons make sense only with single numeric value.
+fn check_num_cmp<T>(
+    r: Option<NumRange<T>>,
+    cmp: CmpType,
+    s: &str,
+    val: &str,
+) -> pareg::Result<Option<NumRange<T>>> {
+    if cmp == CmpType::Regex {
+        return ArgError::failed_to_parse(
+            "Regex is not supported for numeric filters.",
+            s,
+        )
+        .spanned(s.len() - val.len() - 1..s.len() - val.len())
+        .err();
+    }
+
+    if cmp == CmpType::Fuzzy {
+        return ArgError::failed_to_parse(
+            "Fuzzy comparison is not supported for numeric filters.",
+            s,
+        )
+        .spanned(s.len() - val.len() - 1..s.len() - val.len())
+        .err();
+    }
+
+    if !cmp.is_ordering() {
+        return Ok(r);
+    }
+
+    match r {
+        Some(NumRange::Range(..)) => {
+            ArgError::failed_to_parse("Cannot compare with range.", s)
+                .spanned(s.len() - val.len()..s.len())
+                .hint("Use `=` or `:` to match range.")
+                .err()
+        }
+        None => ArgError::failed_to_parse("Missing value to compare with.", s)
+            .spanned(s.len()..s.len())
+            .err(),
+        r => Ok(r),
+    }
+}
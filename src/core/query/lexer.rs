@@ -43,7 +43,7 @@ pub struct Lexer<'a> {
 }
 
 impl<'a> Lexer<'a> {
-    const SPECIAL: &'static str = "[]{}.+/@";
+    pub const SPECIAL: &'static str = "[]{}.+/@";
 
     pub fn new(s: &'a str) -> Self {
         Self {
@@ -259,9 +259,17 @@ impl<'a> Lexer<'a> {
     }
 
     fn find_special(&self) -> Option<(usize, char)> {
-        self.data
-            .char_indices()
-            .find(|(_, c)| Self::SPECIAL.contains(*c))
+        let mut chars = self.data.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            // `..` is range, not two `.` (and).
+            if c == '.' && chars.next_if(|(_, c)| *c == '.').is_some() {
+                continue;
+            }
+            if Self::SPECIAL.contains(c) {
+                return Some((i, c));
+            }
+        }
+        None
     }
 
     fn find_char(&self, chr: char) -> Option<usize> {
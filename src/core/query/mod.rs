@@ -2,6 +2,7 @@ mod base;
 mod composed_filter;
 mod filter;
 mod lexer;
+mod num_range;
 mod order;
 mod parser;
 mod unique;
@@ -19,9 +20,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::core::{Result, player::Player, query::unique::Unique};
 
-pub use self::{base::*, composed_filter::*, filter::*, order::*};
+pub use self::{
+    base::*, composed_filter::*, filter::*, num_range::*, order::*,
+};
 
-use super::library::{Library, Song, SongId};
+use super::library::{Library, MetaChange, Song, SongId};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Query {
@@ -58,7 +61,7 @@ impl Query {
     pub fn get_ids(
         &self,
         lib: &Library,
-        simple: bool,
+        opts: SortOpts,
         base: Base,
         player: &Player,
     ) -> Result<Vec<SongId>> {
@@ -73,14 +76,24 @@ impl Query {
             bases.push(base.iter(lib, player)?);
         }
 
+        let cand = self.filter.candidates(lib.search_index());
         let mut res = bases
             .into_iter()
             .flatten()
-            .filter(|i| self.filter.matches(&lib[i], &mut buf))
+            .filter(|i| {
+                cand.as_ref().is_none_or(|c| c.contains(*i))
+                    && self.filter.matches(&lib[i], &mut buf)
+            })
             .collect_vec();
 
         if let Some(s) = self.sort {
-            s.sort(lib, &mut res[..], simple, None);
+            if s.field == OrderField::Relevance {
+                s.sort_score(&mut res, |i| {
+                    self.filter.score(&lib[i], &mut buf)
+                });
+            } else {
+                s.sort(lib, &mut res[..], opts, None);
+            }
         }
 
         if let Some(u) = self.unique {
@@ -90,15 +103,34 @@ impl Query {
         Ok(res)
     }
 
+    /// Gets the metadata of songs that the result of the query depends on.
+    /// `base` is used if the query has no bases.
+    pub fn meta_deps(&self, base: &Base) -> MetaChange {
+        let mut res = self.filter.meta_deps();
+        if self.bases.is_empty() {
+            res |= base.meta_deps();
+        }
+        for b in &self.bases {
+            res |= b.meta_deps();
+        }
+        if let Some(s) = self.sort {
+            res |= s.meta_deps();
+        }
+        if let Some(u) = self.unique {
+            res |= u.meta_deps();
+        }
+        res
+    }
+
     pub fn clone_songs(
         &self,
         lib: &Library,
-        simple: bool,
+        opts: SortOpts,
         base: Base,
         player: &Player,
     ) -> Result<Vec<Song>> {
         let mut res = self
-            .get_ids(lib, simple, base, player)?
+            .get_ids(lib, opts, base, player)?
             .into_iter()
             .map(|a| lib[a].clone())
             .collect_vec();
@@ -0,0 +1,98 @@
+use std::fmt::{Display, Formatter};
+
+use pareg::FromArg;
+use serde::{Deserialize, Serialize};
+
+use super::CmpType;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Value of numeric filter. It is either single value or inclusive range.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum NumRange<T> {
+    /// Single value.
+    Exact(T),
+    /// Inclusive range. Bound that is [`None`] is unbounded.
+    Range(Option<T>, Option<T>),
+}
+
+impl<T> NumRange<T> {
+    /// Parses the range using the given parser for the values.
+    pub fn parse_with<'a>(
+        s: &'a str,
+        f: impl Fn(&'a str) -> pareg::Result<T>,
+    ) -> pareg::Result<Self> {
+        let Some(p) = s.find("..") else {
+            return f(s).map(Self::Exact);
+        };
+
+        let start = &s[..p];
+        let end = &s[p + 2..];
+
+        let start = if start.is_empty() {
+            None
+        } else {
+            Some(f(start)?)
+        };
+        let end = if end.is_empty() {
+            None
+        } else {
+            Some(f(end).map_err(|e| e.shift_span(p + 2, s.to_string()))?)
+        };
+
+        Ok(Self::Range(start, end))
+    }
+
+    /// Writes the range using the given function to write the values.
+    pub fn fmt_with(
+        &self,
+        f: &mut Formatter<'_>,
+        w: impl Fn(&T, &mut Formatter<'_>) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        match self {
+            Self::Exact(v) => w(v, f),
+            Self::Range(s, e) => {
+                if let Some(s) = s {
+                    w(s, f)?;
+                }
+                f.write_str("..")?;
+                if let Some(e) = e {
+                    w(e, f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: PartialOrd> NumRange<T> {
+    /// Checks if the value matches with the given comparison.
+    pub fn matches(&self, v: &T, cmp: CmpType) -> bool {
+        match (self, cmp) {
+            (Self::Exact(e), CmpType::Less) => v < e,
+            (Self::Exact(e), CmpType::LessEq) => v <= e,
+            (Self::Exact(e), CmpType::Greater) => v > e,
+            (Self::Exact(e), CmpType::GreaterEq) => v >= e,
+            (Self::Exact(e), _) => v == e,
+            (Self::Range(s, e), _) => {
+                s.as_ref().is_none_or(|s| v >= s)
+                    && e.as_ref().is_none_or(|e| v <= e)
+            }
+        }
+    }
+}
+
+impl<'a, T: FromArg<'a>> FromArg<'a> for NumRange<T> {
+    fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+        Self::parse_with(arg, T::from_arg)
+    }
+}
+
+impl<T: Display> Display for NumRange<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_with(f, |v, f| write!(f, "{v}"))
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     cmp::Reverse,
     fmt::{Display, Write},
     str::FromStr,
+    sync::Arc,
 };
 
 use itertools::PeekingNext;
@@ -9,7 +10,10 @@ use pareg::{ArgError, FromArg, FromArgStr};
 use rand::{rng, seq::SliceRandom};
 use serde::{Deserialize, Serialize};
 
-use crate::core::library::{Library, SongId};
+use crate::{
+    core::library::{Library, MetaChange, Song, SongId},
+    ext::NaturalKey,
+};
 
 //===========================================================================//
 //                                   Public                                  //
@@ -29,6 +33,15 @@ pub struct SongOrder {
     pub reverse: bool,
 }
 
+/// Default options for sorting songs.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SortOpts {
+    /// Use simple sorting if it is not specified by the order.
+    pub simple: bool,
+    /// Compare strings with natural accent insensitive collation.
+    pub natural: bool,
+}
+
 /// Describes the main ordering field.
 #[derive(
     Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, FromArg,
@@ -50,9 +63,25 @@ pub enum OrderField {
     /// Order by song artist.
     #[arg("p", "art", "performer", "auth", "author")]
     Artist,
+    /// Order by song composer.
+    #[arg("c", "comp")]
+    Composer,
+    /// Order by song conductor.
+    #[arg("cond")]
+    Conductor,
+    /// Order by song performer.
+    // `performer` is alias of artist, so the name of the variant is not used.
+    #[arg(exact, "pf", "perf", "performers")]
+    Performer,
+    /// Order by song lyricist.
+    #[arg("wr", "writer")]
+    Lyricist,
     /// Order by song album.
     #[arg("a", "alb")]
     Album,
+    /// Order by the album artist.
+    #[arg("aa", "ap", "album-artist")]
+    AlbumArtist,
     /// Order by the track number.
     #[arg("t", "trk", "track-number")]
     Track,
@@ -65,11 +94,18 @@ pub enum OrderField {
     /// Order by total track length.
     #[arg("len")]
     Length,
+    /// Order by the rating.
+    #[arg("r")]
+    Rating,
     /// Order by the genre.
     #[arg("g")]
     Genre,
     /// Order by tags.
     Tag,
+    /// Order by relevance to the filter of the query. The most relevant
+    /// songs are first.
+    #[arg("rel")]
+    Relevance,
 }
 
 impl SongOrder {
@@ -84,14 +120,15 @@ impl SongOrder {
     /// Sorts the songs and updates the position of cur if set.
     ///
     /// - `songs`: Songs to order.
-    /// - `simple`: Defaule value for *enable simple sorting* when it is unset.
+    /// - `opts`: Default sorting options. `simple` is used when simple sorting
+    ///   is not set in the order.
     /// - `cur`: Index to the array of cur. When [`Some`] it is updated after
     ///   the sorting so that it points to the same song.
     pub fn sort(
         &self,
         lib: &Library,
         songs: &mut [SongId],
-        simple: bool,
+        opts: SortOpts,
         cur: Option<&mut usize>,
     ) {
         let cur = if let Some(cur) = cur {
@@ -106,15 +143,31 @@ impl SongOrder {
             OrderField::Reverse => self.reverse(songs),
             OrderField::Randomize => self.randomize(songs),
             OrderField::Path => self.path(lib, songs),
-            OrderField::Title => self.title(lib, songs),
-            OrderField::Artist => self.artist(lib, simple, songs),
-            OrderField::Album => self.album(lib, simple, songs),
+            OrderField::Title => self.title(lib, opts, songs),
+            OrderField::Artist => self.artist(lib, opts, songs),
+            OrderField::Composer => {
+                self.role(lib, opts, songs, Song::composers)
+            }
+            OrderField::Conductor => {
+                self.role(lib, opts, songs, Song::conductors)
+            }
+            OrderField::Performer => {
+                self.role(lib, opts, songs, Song::performers)
+            }
+            OrderField::Lyricist => {
+                self.role(lib, opts, songs, Song::lyricists)
+            }
+            OrderField::Album => self.album(lib, opts, songs),
+            OrderField::AlbumArtist => self.album_artist(lib, opts, songs),
             OrderField::Track => self.track(lib, songs),
-            OrderField::Disc => self.disc(lib, simple, songs),
-            OrderField::Year => self.year(lib, simple, songs),
+            OrderField::Disc => self.disc(lib, opts, songs),
+            OrderField::Year => self.year(lib, opts, songs),
             OrderField::Length => self.length(lib, songs),
-            OrderField::Genre => self.genre(lib, songs),
-            OrderField::Tag => self.tag(lib, songs),
+            OrderField::Rating => self.rating(lib, songs),
+            OrderField::Genre => self.genre(lib, opts, songs),
+            OrderField::Tag => self.tag(lib, opts, songs),
+            // Relevance is known only to the query, see `sort_score`.
+            OrderField::Relevance => self.same(songs),
         }
 
         if let Some((idx, song)) = cur
@@ -123,6 +176,42 @@ impl SongOrder {
             *idx = songs.iter().position(|s| *s == song).unwrap();
         }
     }
+
+    /// Sorts the songs by the given score. Songs with larger score are first.
+    /// Songs with the same score keep their order.
+    pub fn sort_score(
+        &self,
+        songs: &mut [SongId],
+        mut score: impl FnMut(SongId) -> f32,
+    ) {
+        let mut scored: Vec<_> =
+            songs.iter().map(|s| (score(*s), *s)).collect();
+        if self.reverse {
+            scored.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        } else {
+            scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        }
+
+        for (d, (_, s)) in songs.iter_mut().zip(scored) {
+            *d = s;
+        }
+    }
+
+    /// Gets the metadata of songs that the order depends on. Relevance
+    /// depends only on the filter of the query.
+    pub fn meta_deps(&self) -> MetaChange {
+        match self.field {
+            OrderField::Same
+            | OrderField::Reverse
+            | OrderField::Randomize
+            | OrderField::Path
+            | OrderField::Relevance => MetaChange::NONE,
+            OrderField::Length => MetaChange::LENGTH,
+            OrderField::Rating => MetaChange::RATING,
+            OrderField::Tag => MetaChange::TAGS,
+            _ => MetaChange::INFO,
+        }
+    }
 }
 
 impl FromStr for SongOrder {
@@ -193,13 +282,20 @@ impl Display for SongOrder {
             OrderField::Path => write!(f, "path"),
             OrderField::Title => write!(f, "n"),
             OrderField::Artist => write!(f, "p"),
+            OrderField::Composer => write!(f, "c"),
+            OrderField::Conductor => write!(f, "cond"),
+            OrderField::Performer => write!(f, "pf"),
+            OrderField::Lyricist => write!(f, "wr"),
             OrderField::Album => write!(f, "a"),
+            OrderField::AlbumArtist => write!(f, "aa"),
             OrderField::Track => write!(f, "t"),
             OrderField::Disc => write!(f, "d"),
             OrderField::Year => write!(f, "date"),
             OrderField::Length => write!(f, "len"),
+            OrderField::Rating => write!(f, "r"),
             OrderField::Genre => write!(f, "g"),
             OrderField::Tag => write!(f, "tag"),
+            OrderField::Relevance => write!(f, "rel"),
         }
     }
 }
@@ -210,16 +306,58 @@ impl FromArgStr for SongOrder {}
 //                                  Private                                  //
 //===========================================================================//
 
+/// Key of string used for sorting.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum StrKey<'a> {
+    Raw(&'a str),
+    Natural(NaturalKey),
+}
+
+impl SortOpts {
+    fn key<'a>(&self, s: &'a str) -> StrKey<'a> {
+        if self.natural {
+            StrKey::Natural(NaturalKey::new(s))
+        } else {
+            StrKey::Raw(s)
+        }
+    }
+
+    fn opt_key<'a>(&self, s: Option<&'a str>) -> Option<StrKey<'a>> {
+        s.map(|s| self.key(s))
+    }
+
+    fn keys<'a, S>(
+        &self,
+        s: impl IntoIterator<Item = &'a S>,
+    ) -> Vec<StrKey<'a>>
+    where
+        S: AsRef<str> + ?Sized + 'a,
+    {
+        s.into_iter().map(|s| self.key(s.as_ref())).collect()
+    }
+
+    /// Key for the artists of the song. Uses the sort name if set.
+    fn artists<'a>(&self, song: &'a Song) -> Vec<StrKey<'a>> {
+        if let Some(a) = song.artist_sort() {
+            vec![self.key(a)]
+        } else {
+            self.keys(song.artists())
+        }
+    }
+}
+
 impl SongOrder {
+    /// Sorts by the given key. The key is computed only once for each song,
+    /// because computing natural keys of strings is expensive.
     fn sort_key<F, O>(&self, songs: &mut [SongId], f: F)
     where
         O: Ord,
         F: Fn(SongId) -> O,
     {
         if self.reverse {
-            songs.sort_by_key(|i| Reverse(f(*i)));
+            songs.sort_by_cached_key(|i| Reverse(f(*i)));
         } else {
-            songs.sort_by_key(|i| f(*i));
+            songs.sort_by_cached_key(|i| f(*i));
         }
     }
 
@@ -243,19 +381,19 @@ impl SongOrder {
         self.sort_key(songs, |s| lib[s].path());
     }
 
-    fn title(&self, lib: &Library, songs: &mut [SongId]) {
-        self.sort_key(songs, |s| lib[s].title());
+    fn title(&self, lib: &Library, opts: SortOpts, songs: &mut [SongId]) {
+        self.sort_key(songs, |s| opts.opt_key(lib[s].sort_title()));
     }
 
-    fn artist(&self, lib: &Library, simple: bool, songs: &mut [SongId]) {
-        if self.simple.unwrap_or(simple) {
-            self.sort_key(songs, |s| lib[s].artists());
+    fn artist(&self, lib: &Library, opts: SortOpts, songs: &mut [SongId]) {
+        if self.simple.unwrap_or(opts.simple) {
+            self.sort_key(songs, |s| opts.artists(&lib[s]));
         } else {
             self.sort_key(songs, |s| {
                 (
-                    lib[s].artists(),
+                    opts.artists(&lib[s]),
                     lib[s].year(),
-                    lib[s].album(),
+                    opts.opt_key(lib[s].sort_album()),
                     lib[s].disc(),
                     lib[s].track(),
                 )
@@ -263,12 +401,59 @@ impl SongOrder {
         }
     }
 
-    fn album(&self, lib: &Library, simple: bool, songs: &mut [SongId]) {
-        if self.simple.unwrap_or(simple) {
-            self.sort_key(songs, |s| lib[s].album());
+    /// Orders by artists in the given role.
+    fn role(
+        &self,
+        lib: &Library,
+        opts: SortOpts,
+        songs: &mut [SongId],
+        role: fn(&Song) -> &[Arc<str>],
+    ) {
+        if self.simple.unwrap_or(opts.simple) {
+            self.sort_key(songs, |s| opts.keys(role(&lib[s])));
+        } else {
+            self.sort_key(songs, |s| {
+                (
+                    opts.keys(role(&lib[s])),
+                    opts.opt_key(lib[s].sort_album()),
+                    lib[s].disc(),
+                    lib[s].track(),
+                )
+            })
+        }
+    }
+
+    fn album(&self, lib: &Library, opts: SortOpts, songs: &mut [SongId]) {
+        if self.simple.unwrap_or(opts.simple) {
+            self.sort_key(songs, |s| opts.opt_key(lib[s].sort_album()));
         } else {
             self.sort_key(songs, |s| {
-                (lib[s].album(), lib[s].disc(), lib[s].track())
+                (
+                    opts.opt_key(lib[s].sort_album()),
+                    lib[s].disc(),
+                    lib[s].track(),
+                )
+            });
+        }
+    }
+
+    fn album_artist(
+        &self,
+        lib: &Library,
+        opts: SortOpts,
+        songs: &mut [SongId],
+    ) {
+        if self.simple.unwrap_or(opts.simple) {
+            self.sort_key(songs, |s| opts.opt_key(lib[s].sort_album_artist()));
+        } else {
+            self.sort_key(songs, |s| {
+                (
+                    opts.opt_key(lib[s].sort_album_artist()),
+                    lib[s].year(),
+                    opts.opt_key(lib[s].sort_album()),
+                    lib[s].disc(),
+                    lib[s].track(),
+                )
             });
         }
     }
@@ -277,20 +462,25 @@ impl SongOrder {
         self.sort_key(songs, |s| lib[s].track());
     }
 
-    fn disc(&self, lib: &Library, simple: bool, songs: &mut [SongId]) {
-        if self.simple.unwrap_or(simple) {
+    fn disc(&self, lib: &Library, opts: SortOpts, songs: &mut [SongId]) {
+        if self.simple.unwrap_or(opts.simple) {
             self.sort_key(songs, |s| lib[s].disc());
         } else {
             self.sort_key(songs, |s| (lib[s].disc(), lib[s].track()));
         }
     }
 
-    fn year(&self, lib: &Library, simple: bool, songs: &mut [SongId]) {
-        if self.simple.unwrap_or(simple) {
+    fn year(&self, lib: &Library, opts: SortOpts, songs: &mut [SongId]) {
+        if self.simple.unwrap_or(opts.simple) {
             self.sort_key(songs, |s| lib[s].year());
         } else {
             self.sort_key(songs, |s| {
-                (lib[s].year(), lib[s].album(), lib[s].disc(), lib[s].track())
+                (
+                    lib[s].year(),
+                    opts.opt_key(lib[s].sort_album()),
+                    lib[s].disc(),
+                    lib[s].track(),
+                )
             });
         }
     }
@@ -299,11 +489,15 @@ impl SongOrder {
         self.sort_key(songs, |s| lib[s].length());
     }
 
-    fn genre(&self, lib: &Library, songs: &mut [SongId]) {
-        self.sort_key(songs, |s| lib[s].genres());
+    fn rating(&self, lib: &Library, songs: &mut [SongId]) {
+        self.sort_key(songs, |s| lib[s].rating());
+    }
+
+    fn genre(&self, lib: &Library, opts: SortOpts, songs: &mut [SongId]) {
+        self.sort_key(songs, |s| opts.keys(lib[s].genres()));
     }
 
-    fn tag(&self, lib: &Library, songs: &mut [SongId]) {
-        self.sort_key(songs, |s| lib[s].tags())
+    fn tag(&self, lib: &Library, opts: SortOpts, songs: &mut [SongId]) {
+        self.sort_key(songs, |s| opts.keys(&lib[s].tags))
     }
 }
@@ -8,7 +8,7 @@ use std::{
 use pareg::FromArg;
 use serde::{Deserialize, Serialize};
 
-use crate::core::library::{Library, Song, SongId};
+use crate::core::library::{Library, MetaChange, Song, SongId};
 
 #[derive(Debug, Copy, Clone, FromArg, Serialize, Deserialize)]
 pub enum Unique {
@@ -18,6 +18,15 @@ pub enum Unique {
     Title,
     #[arg("p", "art", "performer", "auth", "author")]
     Artist,
+    #[arg("c", "comp")]
+    Composer,
+    #[arg("cond")]
+    Conductor,
+    // `performer` is alias of artist, so the name of the variant is not used.
+    #[arg(exact, "pf", "perf", "performers")]
+    Performer,
+    #[arg("wr", "writer")]
+    Lyricist,
     #[arg("a", "alb")]
     Album,
     #[arg("t", "trk", "track-number")]
@@ -34,11 +43,33 @@ pub enum Unique {
 }
 
 impl Unique {
+    /// Gets the metadata of songs that the uniqueness depends on.
+    pub fn meta_deps(&self) -> MetaChange {
+        match self {
+            Self::Songs => MetaChange::NONE,
+            Self::Length => MetaChange::LENGTH,
+            Self::Tag => MetaChange::TAGS,
+            _ => MetaChange::INFO,
+        }
+    }
+
     pub fn filter_song(&self, data: &mut Vec<Song>) {
         match self {
             Self::Songs => unique_filter_song(data, |a| a.path().into()),
             Self::Title => unique_filter_song(data, |a| a.title_str().into()),
             Self::Artist => unique_filter_song(data, |a| a.artists().into()),
+            Self::Composer => {
+                unique_filter_song(data, |a| a.composers().into())
+            }
+            Self::Conductor => {
+                unique_filter_song(data, |a| a.conductors().into())
+            }
+            Self::Performer => {
+                unique_filter_song(data, |a| a.performers().into())
+            }
+            Self::Lyricist => {
+                unique_filter_song(data, |a| a.lyricists().into())
+            }
             Self::Album => unique_filter_song(data, |a| a.album_str().into()),
             Self::Track => unique_filter_song(data, |a| {
                 Cow::<u32>::Owned(a.track().unwrap_or_default())
@@ -66,6 +97,18 @@ impl Unique {
             Self::Artist => {
                 unique_filter_id(data, |a| lib[a].artists().into())
             }
+            Self::Composer => {
+                unique_filter_id(data, |a| lib[a].composers().into())
+            }
+            Self::Conductor => {
+                unique_filter_id(data, |a| lib[a].conductors().into())
+            }
+            Self::Performer => {
+                unique_filter_id(data, |a| lib[a].performers().into())
+            }
+            Self::Lyricist => {
+                unique_filter_id(data, |a| lib[a].lyricists().into())
+            }
             Self::Album => {
                 unique_filter_id(data, |a| lib[a].album_str().into())
             }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use anyhow::anyhow;
 use http_body_util::{BodyExt, Full};
 use hyper::{
@@ -11,7 +13,7 @@ use url::Url;
 
 use crate::core::{
     AnyControlMsg, Error, LogResult, Result,
-    library::Song,
+    library::{Lyrics, Song},
     query::Query,
     server::{Info, RepMsg},
 };
@@ -134,6 +136,44 @@ impl Client {
             _ => Error::invalid_value().msg("Unexpected response.").err(),
         }
     }
+
+    pub async fn req_lyrics(&mut self) -> Result<Option<Arc<Lyrics>>> {
+        let url = Url::parse_with_params(
+            &("http://".to_string() + &self.authority + "/api/req"),
+            &[("lyrics", "")],
+        )?;
+
+        let req = Request::builder()
+            .uri(url.to_string())
+            .header(hyper::header::HOST, url.authority())
+            .body(Full::new(Bytes::new()))?;
+
+        self.sender.ready().await?;
+        let res = self.sender.send_request(req).await?;
+
+        if !res.status().is_success() {
+            return Err(Error::http(
+                res.status().as_u16(),
+                body_to_string(res).await?,
+            ));
+        }
+
+        let mut res =
+            serde_json::from_slice::<Vec<RepMsg>>(&body_to_vec(res).await?)?;
+
+        if res.len() != 1 {
+            return Error::invalid_value()
+                .msg("Response didn't contain correct amount of data.")
+                .err();
+        }
+
+        let res = res.pop().unwrap();
+        match res {
+            RepMsg::Lyrics(l) => Ok(l),
+            RepMsg::Error(e) => Error::other(anyhow!(e)).err(),
+            _ => Error::invalid_value().msg("Unexpected response.").err(),
+        }
+    }
 }
 
 fn ctrl_to_query(msg: &AnyControlMsg) -> (String, String) {
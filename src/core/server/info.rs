@@ -1,7 +1,11 @@
 use raplay::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use crate::core::{Alias, library::Song, player::AddPolicy};
+use crate::core::{
+    Alias,
+    library::Song,
+    player::{AddPolicy, SleepMode},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Info {
@@ -31,4 +35,7 @@ pub struct Info {
     pub playlist_end: Option<Alias>,
     /// Playlist add policy
     pub playlist_add_policy: AddPolicy,
+    /// Remaining time or songs of the sleep timer.
+    #[serde(default)]
+    pub sleep_timer: Option<SleepMode>,
 }
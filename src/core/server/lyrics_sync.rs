@@ -0,0 +1,202 @@
+use std::{mem, sync::Arc, time::Instant};
+
+use tokio::sync::oneshot;
+
+use crate::core::{
+    AppCtrl, Error, LogResult, Msg, Result, UampApp,
+    library::{Lyrics, SongId},
+};
+
+use super::SubMsg;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Lyrics of the current song and the state of their synchronization with
+/// clients.
+#[derive(Debug, Default)]
+pub struct LyricsSync {
+    /// Lyrics of the current song. [`None`] if no lyrics were requested yet.
+    song: Option<SongLyrics>,
+    /// Line that was last sent to clients.
+    line: Option<usize>,
+    /// When the lyrics routine is scheduled to run to update the line.
+    wake: Option<Instant>,
+}
+
+impl UampApp {
+    /// Gets the lyrics of the currently playing song. The lyrics are loaded
+    /// in background only once for each song. The result is received when
+    /// they are loaded.
+    ///
+    /// # Errors
+    /// - Failed to load the lyrics.
+    pub fn current_lyrics(
+        &mut self,
+        ctrl: &mut AppCtrl,
+    ) -> oneshot::Receiver<Result<Option<Arc<Lyrics>>>> {
+        let (send, recv) = oneshot::channel();
+        let Some(song) = self.player.now_playing() else {
+            _ = send.send(Ok(None));
+            return recv;
+        };
+
+        if self.lyrics.song.as_ref().is_none_or(|l| l.song != song) {
+            self.start_lyrics_load(ctrl, song);
+        }
+        if let Some(l) = &mut self.lyrics.song {
+            if l.loaded {
+                _ = send.send(Ok(l.lyrics.clone()));
+            } else {
+                l.waiting.push(send);
+            }
+        }
+        recv
+    }
+
+    /// Sends the current line of synced lyrics to clients when it changes
+    /// and schedules the next update.
+    pub(in crate::core) fn lyrics_routine(
+        &mut self,
+        ctrl: &mut AppCtrl,
+        now: Instant,
+    ) {
+        if self.jobs.server.is_none() {
+            return;
+        }
+
+        let lyrics = match (self.player.now_playing(), &self.lyrics.song) {
+            (None, _) => None,
+            (Some(s), Some(l)) if l.song == s => l.lyrics.clone(),
+            (Some(s), _) => {
+                self.start_lyrics_load(ctrl, s);
+                None
+            }
+        };
+
+        let mut line = None;
+        if let Some(lyrics) = lyrics.filter(|l| l.synced)
+            && let Some(ts) = self.player.timestamp()
+        {
+            line = lyrics.line_at(ts.current);
+
+            if self.player.is_playing()
+                && let Some(next) = lyrics.next_time(ts.current)
+            {
+                let wake = now + (next - ts.current);
+                // There may be pending wake that is sooner.
+                if self.lyrics.wake.is_none_or(|w| w <= now || w > wake) {
+                    self.lyrics.wake = Some(wake);
+                    ctrl.task(async move {
+                        tokio::time::sleep_until(wake.into()).await;
+                        Msg::None
+                    });
+                }
+            }
+        }
+
+        // Clients are also notified when the song changes or when it has no
+        // synced lyrics so that they don't show line of the previous song.
+        if mem::replace(&mut self.lyrics.line, line) != line {
+            self.client_update(SubMsg::LyricsLine(line));
+        }
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+/// Lyrics of single song.
+#[derive(Debug)]
+struct SongLyrics {
+    /// Song to which the lyrics belong.
+    song: SongId,
+    lyrics: Option<Arc<Lyrics>>,
+    /// False while the lyrics are loading in background.
+    loaded: bool,
+    /// Requests waiting for the lyrics to load.
+    waiting: Vec<oneshot::Sender<Result<Option<Arc<Lyrics>>>>>,
+}
+
+impl SongLyrics {
+    /// Sets the loaded lyrics and sends them to the waiting requests.
+    fn finish(&mut self, res: Result<Option<Arc<Lyrics>>>) {
+        self.loaded = true;
+        let mut waiting = mem::take(&mut self.waiting).into_iter();
+        match res {
+            Ok(l) => {
+                for w in waiting {
+                    _ = w.send(Ok(l.clone()));
+                }
+                self.lyrics = l;
+            }
+            Err(e) => {
+                // Don't try to load the lyrics again if it failed. Only the
+                // first request gets the error, the others get no lyrics as
+                // if they came after the failure.
+                match waiting.next() {
+                    Some(w) => _ = w.send(Err(e)),
+                    None => {
+                        _ = Err::<(), _>(e)
+                            .or_log_err("Failed to load lyrics.")
+                    }
+                }
+                for w in waiting {
+                    _ = w.send(Ok(None));
+                }
+            }
+        }
+    }
+}
+
+impl UampApp {
+    /// Loads the lyrics of the given song in background. Requests waiting for
+    /// the lyrics of the previous song will get the lyrics of this song. The
+    /// lyrics routine will run again once they are loaded.
+    fn start_lyrics_load(&mut self, ctrl: &mut AppCtrl, song: SongId) {
+        let waiting = match self.lyrics.song.take() {
+            Some(l) if !l.loaded => l.waiting,
+            _ => vec![],
+        };
+        let l = self.lyrics.song.insert(SongLyrics {
+            song,
+            lyrics: None,
+            loaded: false,
+            waiting,
+        });
+
+        // Lyrics of file split by cue sheet are not timed by the tracks.
+        let song_data = &self.library[song];
+        if song_data.range().is_some() {
+            l.finish(Ok(None));
+            return;
+        }
+        let path = song_data.path().to_owned();
+
+        let task = move || Lyrics::load(path).map(|l| l.map(Arc::new));
+        ctrl.task(async move {
+            let res = tokio::task::spawn_blocking(task)
+                .await
+                .map_err(Error::from)
+                .flatten();
+            Msg::fn_delegate(move |app, _| {
+                app.finish_lyrics_load(song, res);
+                Ok(vec![])
+            })
+        });
+    }
+
+    fn finish_lyrics_load(
+        &mut self,
+        song: SongId,
+        res: Result<Option<Arc<Lyrics>>>,
+    ) {
+        match &mut self.lyrics.song {
+            Some(l) if l.song == song && !l.loaded => l.finish(res),
+            // The song has changed in the meantime.
+            _ => {}
+        }
+    }
+}
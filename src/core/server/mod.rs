@@ -1,4 +1,5 @@
 mod info;
+mod lyrics_sync;
 mod rep_msg;
 mod req_msg;
 mod server_data;
@@ -32,8 +33,8 @@ use crate::core::{
 pub mod client;
 
 pub use self::{
-    info::*, rep_msg::*, req_msg::*, server_data::*, snd_msg::*, sub_msg::*,
-    uamp_service::*,
+    info::*, lyrics_sync::*, rep_msg::*, req_msg::*, server_data::*,
+    snd_msg::*, sub_msg::*, uamp_service::*,
 };
 
 struct Server {
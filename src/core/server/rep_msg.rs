@@ -1,6 +1,8 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
 
-use crate::core::library::Song;
+use crate::core::library::{Lyrics, Song};
 
 use super::Info;
 
@@ -8,5 +10,6 @@ use super::Info;
 pub enum RepMsg {
     Info(Box<Info>),
     Query(Vec<Song>),
+    Lyrics(Option<Arc<Lyrics>>),
     Error(String),
 }
@@ -9,6 +9,8 @@ pub enum ReqMsg {
     Info(usize, usize),
     /// Query for songs
     Query(Query),
+    /// Request the lyrics of the current song.
+    Lyrics,
 }
 
 impl ReqMsg {
@@ -22,6 +24,7 @@ impl ReqMsg {
             "query" | "list" | "l" => Ok(ReqMsg::Query(
                 parse_arg::<Option<_>>(v)?.unwrap_or_default(),
             )),
+            "lyrics" | "lyr" => Ok(ReqMsg::Lyrics),
             _ => ArgError::failed_to_parse("Invalid request type.", k).err(),
         }
     }
@@ -1,9 +1,13 @@
-use crate::core::{AnyControlMsg, server::ReqMsg};
+use std::path::PathBuf;
+
+use crate::core::{AnyControlMsg, query::Query, server::ReqMsg};
 
 #[derive(Debug)]
 pub enum SndMsg {
     Ctrl(AnyControlMsg),
     Req(ReqMsg),
+    /// Query songs and export them to the playlist file.
+    Export(PathBuf, Query),
 }
 
 impl From<AnyControlMsg> for SndMsg {
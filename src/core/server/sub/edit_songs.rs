@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+use crate::core::library::{SongEdit, SongId};
+
+#[derive(Debug, Serialize)]
+pub struct EditSongs {
+    songs: Vec<SongId>,
+    edit: SongEdit,
+}
+
+impl EditSongs {
+    pub fn new(songs: Vec<SongId>, edit: SongEdit) -> Self {
+        Self { songs, edit }
+    }
+}
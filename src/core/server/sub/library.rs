@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use serde::Serialize;
 
-use crate::core::library::{self, Albums, Artists, Song, Tags};
+use crate::core::library::{self, Albums, Artists, Playlists, Song, Tags};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct Library {
@@ -11,6 +11,7 @@ pub struct Library {
     artists: Arc<Artists>,
     albums: Arc<Albums>,
     tags: Arc<Tags>,
+    playlists: Arc<Playlists>,
 }
 
 impl Library {
@@ -21,6 +22,7 @@ impl Library {
             artists: lib.clone_artists().into(),
             albums: lib.clone_albums().into(),
             tags: lib.clone_tags().into(),
+            playlists: lib.clone_playlists().into(),
         }
     }
 }
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+/// Progress of library load.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadProgress {
+    /// Number of songs that are already loaded.
+    done: usize,
+    /// Total number of songs that will be loaded.
+    total: usize,
+}
+
+impl LoadProgress {
+    pub fn new(done: usize, total: usize) -> Self {
+        Self { done, total }
+    }
+}
@@ -1,7 +1,10 @@
 mod add_tag;
 mod config;
+mod edit_songs;
 mod insert_into_playlist;
 mod library;
+mod load_progress;
+mod named_playlist;
 mod new_server;
 mod play_tmp;
 mod player;
@@ -13,11 +16,16 @@ mod remove_from_playlist;
 mod remove_tag;
 mod reorder_playlist_stack;
 mod set_all;
+mod set_bookmarks;
 mod set_playlist;
+mod set_rating;
+mod song_stats;
 
 pub use self::{
-    add_tag::*, config::*, insert_into_playlist::*, library::*, new_server::*,
-    play_tmp::*, player::*, playlist::*, playlist_jump::*, pop_playlist::*,
+    add_tag::*, config::*, edit_songs::*, insert_into_playlist::*, library::*,
+    load_progress::*, named_playlist::*, new_server::*, play_tmp::*,
+    player::*, playlist::*, playlist_jump::*, pop_playlist::*,
     pop_set_playlist::*, remove_from_playlist::*, remove_tag::*,
-    reorder_playlist_stack::*, set_all::*, set_playlist::*,
+    reorder_playlist_stack::*, set_all::*, set_bookmarks::*, set_playlist::*,
+    set_rating::*, song_stats::*,
 };
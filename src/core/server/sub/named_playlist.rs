@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::core::library::SongId;
+
+#[derive(Debug, Serialize)]
+pub struct NamedPlaylist {
+    name: Arc<str>,
+    songs: Vec<SongId>,
+}
+
+impl NamedPlaylist {
+    pub fn new(name: Arc<str>, songs: Vec<SongId>) -> Self {
+        Self { name, songs }
+    }
+}
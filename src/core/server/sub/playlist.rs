@@ -5,7 +5,7 @@ use serde::Serialize;
 use crate::core::{
     Alias,
     library::SongId,
-    player::{self, AddPolicy},
+    player::{self, AddPolicy, RepeatMode},
 };
 
 #[derive(Debug, Clone, Serialize)]
@@ -15,6 +15,9 @@ pub struct Playlist {
     play_pos: Option<Duration>,
     on_end: Option<Alias>,
     add_policy: AddPolicy,
+    smart: Option<String>,
+    repeat: RepeatMode,
+    shuffled: bool,
 }
 
 impl Playlist {
@@ -25,6 +28,9 @@ impl Playlist {
             play_pos: pl.get_play_pos(),
             on_end: pl.on_end.clone(),
             add_policy: pl.add_policy,
+            smart: pl.smart.clone(),
+            repeat: pl.repeat,
+            shuffled: pl.is_shuffled(),
         }
     }
 }
@@ -0,0 +1,18 @@
+use serde::Serialize;
+
+use crate::core::library::{Bookmark, Song, SongId};
+
+#[derive(Debug, Serialize)]
+pub struct SetBookmarks {
+    song: SongId,
+    bookmarks: Vec<Bookmark>,
+}
+
+impl SetBookmarks {
+    pub fn new(song: SongId, s: &Song) -> Self {
+        Self {
+            song,
+            bookmarks: s.bookmarks().to_vec(),
+        }
+    }
+}
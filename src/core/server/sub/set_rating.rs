@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+use crate::core::library::SongId;
+
+#[derive(Debug, Serialize)]
+pub struct SetRating {
+    songs: Vec<SongId>,
+    rating: Option<u8>,
+}
+
+impl SetRating {
+    pub fn new(songs: Vec<SongId>, rating: Option<u8>) -> Self {
+        Self { songs, rating }
+    }
+}
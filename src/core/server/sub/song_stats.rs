@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::core::library::{Song, SongId};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SongStats {
+    id: SongId,
+    play_count: u32,
+    skip_count: u32,
+    last_played: Option<u64>,
+    resume_pos: Option<Duration>,
+}
+
+impl SongStats {
+    pub fn new(id: SongId, song: &Song) -> Self {
+        Self {
+            id,
+            play_count: song.play_count(),
+            skip_count: song.skip_count(),
+            last_played: song.last_played(),
+            resume_pos: song.resume_pos(),
+        }
+    }
+}
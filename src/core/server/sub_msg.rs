@@ -6,11 +6,12 @@ use uamp_proc::VariantArray;
 
 use crate::core::{
     Alias, Result,
-    player::{AddPolicy, Playback},
+    player::{AddPolicy, Playback, RepeatMode, SleepMode},
     server::sub::{
-        AddTag, Config, InsertIntoPlaylist, NewServer, PlayTmp, PlaylistJump,
-        PopPlaylist, PopSetPlaylist, RemoveFromPlaylist, RemoveTag,
-        ReorderPlaylistStack, SetAll, SetPlaylist,
+        AddTag, Config, EditSongs, InsertIntoPlaylist, LoadProgress,
+        NamedPlaylist, NewServer, PlayTmp, PlaylistJump, PopPlaylist,
+        PopSetPlaylist, RemoveFromPlaylist, RemoveTag, ReorderPlaylistStack,
+        SetAll, SetBookmarks, SetPlaylist, SetRating, SongStats,
     },
 };
 
@@ -50,6 +51,9 @@ pub enum SubMsg {
     // Sets the playlis add policy of the current playlist
     #[list_name("set-playlist-add-policy")]
     SetPlaylistAddPolicy(AddPolicy),
+    // Sets the repeat mode of the current playlist
+    #[list_name("set-playlist-repeat")]
+    SetPlaylistRepeat(RepeatMode),
     // Sets the playlist end action
     #[list_name("set-playlist-end-action")]
     SetPlaylistEndAction(Arc<Option<Alias>>),
@@ -98,6 +102,35 @@ pub enum SubMsg {
     // Remove the given tag from all of the given songs.
     #[list_name("remove-tag")]
     RemoveTag(Arc<RemoveTag>),
+    // Metadata of the given songs was changed.
+    #[list_name("edit-songs")]
+    EditSongs(Arc<EditSongs>),
+    // Play statistics of the song have changed.
+    #[list_name("song-stats")]
+    SongStats(SongStats),
+    // Rating of the given songs was changed.
+    #[list_name("set-rating")]
+    SetRating(Arc<SetRating>),
+    // Bookmarks of the song have changed.
+    #[list_name("set-bookmarks")]
+    SetBookmarks(Arc<SetBookmarks>),
+    // Named playlist was created or its songs have changed.
+    #[list_name("set-named-playlist")]
+    SetNamedPlaylist(Arc<NamedPlaylist>),
+    // Named playlist with the given name was deleted.
+    #[list_name("delete-named-playlist")]
+    DeleteNamedPlaylist(Arc<str>),
+    // Line of the synced lyrics of the current song has changed. It is index
+    // of the line or null if no line is sung.
+    #[list_name("lyrics-line")]
+    LyricsLine(Option<usize>),
+    // Progress of loading new songs to the library.
+    #[list_name("library-load-progress")]
+    LibraryLoadProgress(LoadProgress),
+    // The sleep timer was set, changed or it has ended. It is the remaining
+    // time or songs or null if the timer is not set.
+    #[list_name("sleep-timer")]
+    SleepTimer(Option<SleepMode>),
 }
 
 impl SubMsg {
@@ -116,6 +149,7 @@ impl SubMsg {
             Self::SetPlaylistAddPolicy(d) => {
                 make_event("set-playlist-add-policy", d)
             }
+            Self::SetPlaylistRepeat(d) => make_event("set-playlist-repeat", d),
             Self::SetPlaylistEndAction(d) => {
                 make_event("set-playlist-end-action", d)
             }
@@ -140,6 +174,19 @@ impl SubMsg {
             Self::RemovePlaylist(d) => make_event("remove-playlist", d),
             Self::AddTag(d) => make_event("add-tag", d),
             Self::RemoveTag(d) => make_event("remove-tag", d),
+            Self::EditSongs(d) => make_event("edit-songs", d),
+            Self::SongStats(d) => make_event("song-stats", d),
+            Self::SetRating(d) => make_event("set-rating", d),
+            Self::SetBookmarks(d) => make_event("set-bookmarks", d),
+            Self::SetNamedPlaylist(d) => make_event("set-named-playlist", d),
+            Self::DeleteNamedPlaylist(d) => {
+                make_event("delete-named-playlist", d)
+            }
+            Self::LyricsLine(d) => make_event("lyrics-line", d),
+            Self::LibraryLoadProgress(d) => {
+                make_event("library-load-progress", d)
+            }
+            Self::SleepTimer(d) => make_event("sleep-timer", d),
         }
     }
 }
@@ -13,10 +13,9 @@ use hyper::{
     Method, Request, Response, Uri,
     body::{Bytes, Frame, Incoming},
 };
-use image::EncodableLayout;
 use itertools::Itertools;
 use pareg::{ArgInto, FromArg};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio::{
     fs::{self, File},
     io::AsyncRead,
@@ -27,10 +26,14 @@ use url::Url;
 
 use crate::{
     core::{
-        AnyControlMsg, ErrKind, Error, IdControlMsg, Msg, Result, RtAndle,
-        UampApp,
+        AnyControlMsg, DataControlMsg, ErrKind, Error, IdControlMsg, Msg,
+        Result, RtAndle, UampApp,
         config::{self, CacheSize},
-        library::{Song, img_lookup::lookup_image_path_rt_thread},
+        control::types::EditSongs,
+        library::{
+            PlaylistFormat, Song, SongEdit,
+            img_lookup::lookup_image_path_rt_thread,
+        },
         query::Query,
         server::{Info, RepMsg, ReqMsg, ServerData, sse_service::SseService},
     },
@@ -40,6 +43,16 @@ use crate::{
 type MyBody = StreamBody<BoxStream<'static, Result<Frame<Bytes>>>>;
 type MyResponse = Response<MyBody>;
 
+/// Body of the edit api.
+#[derive(Debug, Deserialize)]
+struct EditReq {
+    query: String,
+    #[serde(default)]
+    write: Option<bool>,
+    #[serde(flatten)]
+    edit: SongEdit,
+}
+
 #[derive(Debug, Clone)]
 pub struct UampService {
     rt: RtAndle,
@@ -89,6 +102,8 @@ impl UampService {
             "/api/sub" => self.handle_sub_api(req).await,
             "/api/marco" => Ok(string_response_cors("polo")),
             "/api/img" => self.handle_img_api(req).await,
+            "/api/export" => self.handle_export_api(req).await,
+            "/api/lyrics" => self.handle_lyrics_api().await,
             v if v.starts_with("/app/") || v == "/app" => {
                 self.handle_app(v.strip_prefix("/app").unwrap()).await
             }
@@ -101,6 +116,7 @@ impl UampService {
             "/api/ctrl" => self.handle_ctrl_post_api(req).await,
             "/api/unidecode" => self.handle_unidecode_api(req).await,
             "/api/check_ctrl" => self.handle_check_ctrl_post_api(req).await,
+            "/api/edit" => self.handle_edit_post_api(req).await,
             _ => Err(Error::http(404, "Unknown POST endpoint.".to_string())),
         }
     }
@@ -213,6 +229,48 @@ impl UampService {
         }
     }
 
+    async fn handle_export_api(
+        &self,
+        req: Request<Incoming>,
+    ) -> Result<MyResponse> {
+        let url = uri_to_url(req.uri())?;
+        let mut format = PlaylistFormat::M3u8;
+        let mut query = Query::default();
+
+        for (k, v) in url.query_pairs() {
+            match k.as_ref() {
+                "format" | "fmt" => format = v.arg_into()?,
+                "query" | "q" => query = v.arg_into()?,
+                _ => {}
+            }
+        }
+
+        let songs = self
+            .rt
+            .request(move |app, _| app.query_response(&query))
+            .await??;
+
+        let mut data = vec![];
+        format.write(&mut data, &songs)?;
+        Ok(download_response(
+            data,
+            format.mime(),
+            &format!("playlist.{}", format.extension()),
+        ))
+    }
+
+    async fn handle_lyrics_api(&self) -> Result<MyResponse> {
+        let lyrics = self
+            .rt
+            .request(move |app, ctrl| app.current_lyrics(ctrl))
+            .await?
+            .await
+            .map_err(|_| {
+                Error::unexpected().msg("Failed to receive lyrics.")
+            })??;
+        json_response(&lyrics)
+    }
+
     async fn handle_app(&self, path: &str) -> Result<MyResponse> {
         let app_path = self.data.client.read().unwrap().clone();
         if fs::metadata(&app_path).await?.is_dir() {
@@ -226,32 +284,7 @@ impl UampService {
         &self,
         mut req: Request<Incoming>,
     ) -> Result<MyResponse> {
-        let len = req
-            .headers()
-            .get("Content-Length")
-            .and_then(|l| l.to_str().ok())
-            .and_then(|a| a.parse().ok());
-        let mut str = req.body_mut().into_data_stream();
-
-        let mut data = if let Some(len) = len {
-            if len > MAX_ACCEPT_LENGTH {
-                return Err(Error::http(413, "Too much data.".to_string()));
-            }
-            Vec::with_capacity(len)
-        } else {
-            vec![]
-        };
-
-        while data.len() <= MAX_ACCEPT_LENGTH
-            && let Some(frame) = str.next().await
-        {
-            let frame = frame?;
-            data.extend(frame);
-        }
-
-        if data.len() > MAX_ACCEPT_LENGTH {
-            return Err(Error::http(413, "Too much data.".to_string()));
-        }
+        let data = read_body_limited(&mut req).await?;
 
         let is_loopback = self.peer.ip().is_loopback();
 
@@ -277,32 +310,9 @@ impl UampService {
         &self,
         mut req: Request<Incoming>,
     ) -> Result<MyResponse> {
-        let len: Option<usize> = req
-            .headers()
-            .get("Content-Length")
-            .and_then(|l| l.to_str().ok())
-            .and_then(|a| a.parse().ok());
-        let mut str = req.body_mut().into_data_stream();
-
-        if let Some(len) = len
-            && len > MAX_ACCEPT_LENGTH
-        {
-            return Err(Error::http(413, "Too much data.".to_string()));
-        }
-
+        let data = read_body_limited(&mut req).await?;
         let mut res = String::new();
-
-        while res.len() <= MAX_ACCEPT_LENGTH
-            && let Some(frame) = str.next().await
-        {
-            let frame = frame?;
-            let s = String::from_utf8_lossy(frame.as_bytes());
-            simpl::to_str(s, &mut res);
-        }
-
-        if res.len() > MAX_ACCEPT_LENGTH {
-            return Err(Error::http(413, "Too much data.".to_string()));
-        }
+        simpl::to_str(String::from_utf8_lossy(&data), &mut res);
 
         Ok(string_response(res))
     }
@@ -311,27 +321,7 @@ impl UampService {
         &self,
         mut req: Request<Incoming>,
     ) -> Result<MyResponse> {
-        let len: Option<usize> = req
-            .headers()
-            .get("Content-Length")
-            .and_then(|l| l.to_str().ok())
-            .and_then(|a| a.parse().ok());
-        let mut str = req.body_mut().into_data_stream();
-
-        if let Some(len) = len
-            && len > MAX_ACCEPT_LENGTH
-        {
-            return Err(Error::http(413, "Too much data.".to_string()));
-        }
-
-        let mut res = vec![];
-
-        while res.len() <= MAX_ACCEPT_LENGTH
-            && let Some(frame) = str.next().await
-        {
-            res.extend(frame?.as_bytes());
-        }
-
+        let res = read_body_limited(&mut req).await?;
         let res = serde_json::from_slice::<Vec<String>>(&res)?
             .into_iter()
             .map(|a| {
@@ -344,10 +334,34 @@ impl UampService {
         json_response(&res)
     }
 
+    async fn handle_edit_post_api(
+        &self,
+        mut req: Request<Incoming>,
+    ) -> Result<MyResponse> {
+        let res = read_body_limited(&mut req).await?;
+
+        let msgs: Vec<_> = serde_json::from_slice::<OneOrVec<EditReq>>(&res)?
+            .into_iter()
+            .map(|e| -> Result<_> {
+                Ok(Msg::DataControl(Box::new(DataControlMsg::EditSongs(
+                    EditSongs {
+                        write: e.write,
+                        edit: e.edit,
+                        query: e.query.arg_into()?,
+                    },
+                ))))
+            })
+            .try_collect()?;
+        self.rt.msgs_result(msgs).await?;
+
+        Ok(string_response("Success!"))
+    }
+
     async fn make_req(&self, k: &str, v: &str) -> Result<RepMsg> {
         match ReqMsg::from_kv(k, v)? {
             ReqMsg::Info(b, a) => self.handle_info_req(b, a).await,
             ReqMsg::Query(q) => self.handle_query_req(q).await,
+            ReqMsg::Lyrics => self.handle_lyrics_req().await,
         }
     }
 
@@ -365,6 +379,15 @@ impl UampService {
             .map(RepMsg::Query)
     }
 
+    async fn handle_lyrics_req(&self) -> Result<RepMsg> {
+        self.rt
+            .request(move |app, ctrl| app.current_lyrics(ctrl))
+            .await?
+            .await
+            .map_err(|_| Error::unexpected().msg("Failed to receive lyrics."))?
+            .map(RepMsg::Lyrics)
+    }
+
     async fn handle_app_dir(
         &self,
         app_path: &Path,
@@ -470,19 +493,52 @@ impl UampApp {
                 .or(self.config.default_playlist_end_action().as_ref())
                 .cloned(),
             playlist_add_policy: self.player.playlist().add_policy,
+            sleep_timer: self.sleep_remaining(),
         })
     }
 
     fn query_response(&mut self, query: &Query) -> Result<Vec<Song>> {
         query.clone_songs(
             &self.library,
-            self.config.simple_sorting(),
+            self.config.sort_opts(),
             self.config.default_base().clone(),
             &self.player,
         )
     }
 }
 
+/// Reads the whole body of the request. Fails if the body is larger than
+/// [`MAX_ACCEPT_LENGTH`].
+async fn read_body_limited(req: &mut Request<Incoming>) -> Result<Vec<u8>> {
+    let len = req
+        .headers()
+        .get("Content-Length")
+        .and_then(|l| l.to_str().ok())
+        .and_then(|a| a.parse().ok());
+    let mut str = req.body_mut().into_data_stream();
+
+    let mut data = if let Some(len) = len {
+        if len > MAX_ACCEPT_LENGTH {
+            return Err(Error::http(413, "Too much data.".to_string()));
+        }
+        Vec::with_capacity(len)
+    } else {
+        vec![]
+    };
+
+    while data.len() <= MAX_ACCEPT_LENGTH
+        && let Some(frame) = str.next().await
+    {
+        data.extend(frame?);
+    }
+
+    if data.len() > MAX_ACCEPT_LENGTH {
+        return Err(Error::http(413, "Too much data.".to_string()));
+    }
+
+    Ok(data)
+}
+
 fn err_response(err: Error) -> MyResponse {
     let code = match err.kind() {
         ErrKind::InvalidOperation
@@ -579,6 +635,19 @@ fn reader_response(
         .expect("Failed to generate reader response. This shouldn't happen.")
 }
 
+fn download_response(b: Vec<u8>, mime: &str, name: &str) -> MyResponse {
+    Response::builder()
+        .status(200)
+        .header("Content-Type", mime)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename=\"{name}\""),
+        )
+        .header("Server", SERVER_HEADER)
+        .body(byte_body(b))
+        .expect("Failed to generate download response. This shouldn't happen.")
+}
+
 fn redirect_response(path: &str, permanent: bool) -> MyResponse {
     Response::builder()
         .status(if permanent { 301 } else { 302 })
@@ -1,9 +1,16 @@
-use crate::core::{library::SongId, player::Playback};
+use crate::core::{
+    library::SongId,
+    player::{Playback, RepeatMode},
+};
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct State {
     pub playback: Playback,
     pub cur_song: Option<(SongId, usize)>,
+    /// Rating of the current song.
+    pub rating: Option<u8>,
     pub volume: f32,
     pub seeked: bool,
+    pub repeat: RepeatMode,
+    pub shuffled: bool,
 }
@@ -1,6 +1,7 @@
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 use std::{
+    collections::BTreeSet,
     env,
     fs::{self, DirEntry, read_dir},
     mem,
@@ -23,7 +24,8 @@ use super::{
     ControlMsg, DataControlMsg,
     config::{Config, ConfigMsg, default_log_dir},
     library::{Library, SongId},
-    player::Player,
+    player::{Player, SleepTimer},
+    server::LyricsSync,
 };
 
 //===========================================================================//
@@ -63,6 +65,17 @@ pub struct UampApp {
     pub(super) state: State,
 
     pub(super) file_watch: Option<INotifyWatcher>,
+
+    /// Watches the search paths for changes.
+    pub(super) library_watch: Option<INotifyWatcher>,
+    /// Changed files that were not yet applied to the library.
+    pub(super) library_changes: BTreeSet<PathBuf>,
+
+    /// Lyrics of the current song.
+    pub(super) lyrics: LyricsSync,
+
+    /// Timer that pauses the playback.
+    pub(super) sleep: SleepTimer,
 }
 
 impl UampApp {
@@ -112,10 +125,20 @@ impl UampApp {
             state: State::default(),
 
             file_watch: config_watch,
+
+            library_watch: None,
+            library_changes: BTreeSet::new(),
+
+            lyrics: LyricsSync::default(),
+
+            sleep: SleepTimer::default(),
         };
 
         app.migrate();
 
+        app.rewatch_library()
+            .or_log_err("Failed to watch library folders.");
+
         if app.config.update_library_on_start() {
             app.start_get_new_songs(ctrl, Default::default())?;
         }
@@ -208,8 +231,15 @@ impl UampApp {
                 .playlist()
                 .current_idx()
                 .map(|i| (self.player.playlist()[i], i)),
+            rating: self
+                .player
+                .playlist()
+                .current()
+                .and_then(|s| self.library[s].rating()),
             volume: self.player.volume(),
             seeked: false,
+            repeat: self.player.playlist().repeat,
+            shuffled: self.player.playlist().is_shuffled(),
         }
     }
 
@@ -219,6 +249,8 @@ impl UampApp {
 
     /// Old song ids were replaced with new valid song ids.
     pub(super) fn id_replace(&mut self, n: impl Fn(SongId, &Library) -> bool) {
+        self.library
+            .retain_playlist_songs(|s, l| !l[s].is_deleted() && !n(s, l));
         self.player_id_replace(n);
     }
 
@@ -228,6 +260,7 @@ impl UampApp {
         closing: bool,
         ctrl: &mut AppCtrl,
     ) -> Result<()> {
+        self.save_resume_pos(closing);
         let mut res = vec![];
         match self.library.start_to_default_json(
             &self.config,
@@ -252,6 +285,7 @@ impl UampApp {
     }
 
     pub(super) fn save_all_block(&mut self, closing: bool) -> Result<()> {
+        self.save_resume_pos(closing);
         let mut res = vec![];
         match self
             .library
@@ -273,6 +307,14 @@ impl UampApp {
         Error::multiple(res)
     }
 
+    /// Saves the resume position of the current song so that it is saved
+    /// with the library. Periodic saves respect `save_playback_pos`.
+    fn save_resume_pos(&mut self, closing: bool) {
+        if closing || self.config.save_playback_pos().save(false) {
+            self.player.save_resume_pos(&mut self.library);
+        }
+    }
+
     /// Starts a thread for handling signals. This is only temorary workaround
     /// until a bug is fixed and `signal_task` will work properly.
     pub(super) fn start_signals(ctrl: &mut AppCtrl) -> Result<()> {
@@ -316,8 +358,11 @@ impl UampApp {
     fn routine(&mut self, errs: &mut Vec<Error>, ctrl: &mut AppCtrl) {
         let now = Instant::now();
 
-        let up = self.library_routine();
-        self.player_routine(now, up);
+        let (up, meta) = self.library_routine();
+        self.player_routine(now, up, meta);
+        self.replay_gain_routine(ctrl);
+        self.sleep_routine(now);
+        self.lyrics_routine(ctrl, now);
         errs.extend(self.config_routine(ctrl, now).err());
         errs.extend(self.restart(ctrl).err());
 
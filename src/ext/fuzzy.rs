@@ -0,0 +1,45 @@
+/// Gets the smallest number of edits (insertions, deletions and
+/// substitutions) needed so that `pat` is substring of `s`.
+///
+/// Strings are compared by bytes, so they should be simplified (see
+/// [`super::simpl`]) before the comparison.
+pub fn substr_distance(pat: &str, s: &str) -> usize {
+    let pat = pat.as_bytes();
+    // Distance of prefix of `pat` from the best substring ending at the
+    // current position in `s`. Substring may start anywhere so the first
+    // item is always 0.
+    let mut row: Vec<_> = (0..=pat.len()).collect();
+    let mut best = pat.len();
+
+    for c in s.bytes() {
+        let mut diag = row[0];
+        for (j, p) in pat.iter().enumerate() {
+            let sub = diag + usize::from(*p != c);
+            diag = row[j + 1];
+            row[j + 1] = sub.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+        best = best.min(row[pat.len()]);
+    }
+
+    best
+}
+
+/// Gets how similar is `pat` to the most similar substring of `s`. The
+/// result is in range `0..=1` where `1` is exact match of the whole string.
+/// Returns [`None`] if more than quarter of the pattern would have to be
+/// edited.
+pub fn similarity(pat: &str, s: &str) -> Option<f32> {
+    if pat.is_empty() {
+        return Some(1.);
+    }
+
+    let dist = substr_distance(pat, s);
+    if dist > pat.len() / 4 {
+        return None;
+    }
+
+    let m = pat.len() as f32;
+    // Matches of larger portion of the string are more similar.
+    let cover = m / s.len().max(pat.len()) as f32;
+    Some((1. - dist as f32 / m) * (0.8 + 0.2 * cover))
+}
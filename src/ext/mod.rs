@@ -1,13 +1,15 @@
 mod alc;
 mod durstr;
+mod natural;
 mod wrap;
 
 //===========================================================================//
 //                                   Public                                  //
 //===========================================================================//
 
+pub mod fuzzy;
 pub mod one_or_vec;
 pub mod simpl;
 pub mod uri;
 
-pub use self::{alc::*, durstr::*, wrap::*};
+pub use self::{alc::*, durstr::*, natural::*, wrap::*};
@@ -0,0 +1,74 @@
+use std::mem;
+
+use unidecode::unidecode_char;
+
+//===========================================================================//
+//                                   Public                                  //
+//===========================================================================//
+
+/// Key for natural ordering of strings.
+///
+/// The string is converted to lowercase ascii without whitespace so that
+/// accents and case are ignored. Sequences of digits are compared by their
+/// numeric value, so `track 2` is before `track 10`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NaturalKey(Vec<Part>);
+
+impl NaturalKey {
+    pub fn new(s: impl AsRef<str>) -> Self {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut num = String::new();
+
+        let chrs = s
+            .as_ref()
+            .chars()
+            .flat_map(|c| unidecode_char(c).chars())
+            .map(|c| c.to_ascii_lowercase());
+        for c in chrs {
+            if c.is_ascii_digit() {
+                push_text(&mut parts, &mut text);
+                num.push(c);
+            } else {
+                push_num(&mut parts, &mut num);
+                if !c.is_ascii_whitespace() {
+                    text.push(c);
+                }
+            }
+        }
+
+        push_text(&mut parts, &mut text);
+        push_num(&mut parts, &mut num);
+
+        Self(parts)
+    }
+}
+
+//===========================================================================//
+//                                  Private                                  //
+//===========================================================================//
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Part {
+    /// Number without leading zeros with its number of digits. The number
+    /// of digits is compared first so that numbers of any length are
+    /// compared by value. Numbers are before text.
+    Num(usize, String),
+    Text(String),
+}
+
+fn push_text(parts: &mut Vec<Part>, text: &mut String) {
+    if !text.is_empty() {
+        parts.push(Part::Text(mem::take(text)));
+    }
+}
+
+fn push_num(parts: &mut Vec<Part>, num: &mut String) {
+    if num.is_empty() {
+        return;
+    }
+
+    let n = num.trim_start_matches('0');
+    parts.push(Part::Num(n.len(), n.to_string()));
+    num.clear();
+}
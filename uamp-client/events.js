@@ -41,6 +41,7 @@ const SSE_HANDLERS = {
     },
     "set-playlist-add-policy": (data) =>
         (app.player.playlist.add_policy = data),
+    "set-playlist-repeat": (data) => (app.player.playlist.repeat = data),
     "set-playlist-end-action": (data) => (app.player.playlist.on_end = data),
     "push-playlist": pushPlaylistEvent,
     "push-playlist-with-cur": (data) => {
@@ -11,8 +11,19 @@ export default class Album {
      * @param {string} artist
      * @param {number|null} year
      * @param {Song[]} songs
+     * @param {{number: number|null, subtitle: string|null, songs: Song[]}[]}
+     *  discs
+     * @param {number} discCount
      */
-    constructor(id, name, artist, year, songs = []) {
+    constructor(
+        id,
+        name,
+        artist,
+        year,
+        songs = [],
+        discs = [],
+        discCount = 0,
+    ) {
         /** @type {string} */
         this.id = id;
         /** @type {string} */
@@ -22,13 +33,30 @@ export default class Album {
         /** @type {number|null} */
         this.year = year;
         /** @type {Sorter} */
-        this.songs = new Sorter("track", songs);
+        this.songs = new Sorter("disc", songs, true, ["track"]);
+        /** Songs split by disc. */
+        this.discs = discs;
+        /** @type {number} */
+        this.discCount = discCount;
     }
 
     static from(id, obj, allSongs) {
         const songs = obj.songs.map((s, _) => allSongs[s]);
+        const discs = (obj.discs ?? []).map((d) => ({
+            number: d.number,
+            subtitle: d.subtitle,
+            songs: d.songs.map((s, _) => allSongs[s]),
+        }));
         const year = songs[0]?.year ?? null;
-        return new Album(id, obj.name, obj.artist, year, songs);
+        return new Album(
+            id,
+            obj.name,
+            obj.artist,
+            year,
+            songs,
+            discs,
+            obj.disc_count ?? 0,
+        );
     }
 
     /**
@@ -54,10 +82,10 @@ export default class Album {
     }
 
     /**
-     * Sorts albums songs by track number
+     * Sorts albums songs by disc and track number
      */
     sortByTrack() {
-        this.songs.sortBy("track", true);
+        this.songs.sortBy("disc", true);
     }
 
     /**
@@ -66,7 +94,7 @@ export default class Album {
      */
     getQuery() {
         const s = (text) => text.replaceAll("/", "//");
-        return `p=/${s(this.artist)}/.a=/${s(this.name)}/@/t`;
+        return `p=/${s(this.artist)}/.a=/${s(this.name)}/@/+d`;
     }
 
     /**
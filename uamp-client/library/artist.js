@@ -19,7 +19,7 @@ export default class Artist {
         /** @type {string} */
         this.name = name;
         /** @type {Sorter} */
-        this.songs = new Sorter("album", songs, true, ["track"]);
+        this.songs = new Sorter("album", songs, true, ["disc", "track"]);
         /** @type {Album[]} */
         this.albums = albums;
     }
@@ -44,6 +44,8 @@ export default class Player {
                 play_pos: null,
                 on_end: null,
                 add_policy: "None",
+                repeat: "None",
+                shuffled: false,
             },
             playlist_stack: [],
             state: "Paused",
@@ -17,6 +17,10 @@ export default class Playlist {
         this.on_end = data.on_end;
         /** @type {string} */
         this.add_policy = data.add_policy;
+        /** @type {string} */
+        this.repeat = data.repeat ?? "None";
+        /** @type {boolean} */
+        this.shuffled = data.shuffled ?? false;
     }
 
     /**
@@ -445,6 +445,9 @@ var settingsGroups = [
             "audio_extensions",
             "recursive_search",
             "update_library_on_start",
+            "watch_library",
+            "scan_threads",
+            "write_tags",
             "remove_missing_on_load",
         ],
     },
@@ -453,7 +456,9 @@ var settingsGroups = [
         settings: [
             "control_aliases",
             "default_playlist_end_action",
+            "smart_playlists",
             "simple_sorting",
+            "natural_sorting",
             "shuffle_current",
         ],
     },
@@ -465,6 +470,11 @@ var settingsGroups = [
             "save_playback_pos",
             "fade_play_pause",
             "gapless",
+            "crossfade",
+            "crossfade_albums",
+            "play_threshold",
+            "replay_gain",
+            "replay_gain_preamp",
             "seek_jump",
             "previous_timeout",
         ],